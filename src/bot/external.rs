@@ -0,0 +1,212 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError},
+    thread,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Bot, Observation};
+use crate::game::Action;
+
+/// What the server writes to a bot, one JSON object per line.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message<'a> {
+    /// It's the bot's turn. It answers with one of `legal_moves`.
+    Turn {
+        observation: &'a Observation,
+        legal_moves: &'a [Action],
+    },
+    /// Someone won. No answer is expected.
+    GameOver {
+        observation: &'a Observation,
+        winner: String,
+    },
+}
+
+/// A bot's answer: where its move is in `legal_moves`, or the move itself.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Reply {
+    Index(usize),
+    Action(Action),
+}
+
+/// A bot running as its own program. It's sent a line of JSON whenever it's
+/// its turn and answers with a line of its own, within the time limit.
+/// Bots that answer late or with something that isn't a legal move draw.
+pub struct External {
+    command: String,
+    child: Child,
+    messages: SyncSender<String>,
+    lines: Receiver<String>,
+    timeout: Duration,
+    log: Option<String>,
+}
+
+impl External {
+    /// Starts `command`, a program followed by its arguments.
+    pub fn spawn(command: &str, timeout: Duration) -> Result<Self, String> {
+        let mut parts = command.split_whitespace();
+        let program = parts
+            .next()
+            .ok_or_else(|| String::from("The bot command is empty"))?;
+
+        // The bot's stderr would draw over the host's screen.
+        let mut child = Command::new(program)
+            .args(parts)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed starting {}: {}", command, e))?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // Writing and reading happen on their own threads so a stuck bot can
+        // be timed out, even one that stopped reading and filled its stdin.
+        let (messages, receiver) = mpsc::sync_channel(1);
+        thread::spawn(move || write_lines(stdin, receiver));
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(External {
+            command: command.to_string(),
+            child,
+            messages,
+            lines,
+            timeout,
+            log: None,
+        })
+    }
+
+    /// Writes the bot's mistakes to the log file at `path` instead of
+    /// stderr.
+    pub fn with_log(mut self, path: &str) -> Self {
+        self.log = Some(path.to_string());
+        self
+    }
+
+    fn report(&self, error: &str) {
+        crate::log(self.log.as_deref(), &format!("{}: {}", self.command, error));
+    }
+
+    /// Queues `message` for the writer thread. Only one message can wait,
+    /// so a bot that stops reading doesn't pile them up.
+    fn send(&mut self, message: &Message) -> Result<(), String> {
+        let mut line = serde_json::to_string(message).unwrap();
+        line.push('\n');
+
+        self.messages.try_send(line).map_err(|e| match e {
+            TrySendError::Full(_) => String::from("The bot isn't reading its input"),
+            TrySendError::Disconnected(_) => String::from("Failed writing to the bot"),
+        })
+    }
+
+    fn ask(&mut self, observation: &Observation, moves: &[Action]) -> Result<Action, String> {
+        // Whatever a bot says after running out of time isn't an answer to
+        // this turn.
+        while self.lines.try_recv().is_ok() {}
+
+        self.send(&Message::Turn {
+            observation,
+            legal_moves: moves,
+        })?;
+
+        let line = self.lines.recv_timeout(self.timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => String::from("The bot ran out of time"),
+            RecvTimeoutError::Disconnected => String::from("The bot quit"),
+        })?;
+
+        let action = match serde_json::from_str(&line) {
+            Ok(Reply::Index(index)) => moves.get(index).cloned(),
+            Ok(Reply::Action(action)) => Some(action).filter(|action| moves.contains(action)),
+            Err(e) => return Err(format!("Invalid answer from the bot: {}", e)),
+        };
+
+        action.ok_or_else(|| String::from("The bot picked a move it can't make"))
+    }
+}
+
+impl Bot for External {
+    fn choose(&mut self, observation: &Observation, moves: &[Action]) -> Action {
+        match self.ask(observation, moves) {
+            Ok(action) => action,
+            Err(e) => {
+                self.report(&e);
+                moves[moves.len() - 1].clone()
+            }
+        }
+    }
+
+    fn game_over(&mut self, observation: &Observation) {
+        let winner = match observation.winner() {
+            Some(winner) => winner.to_string(),
+            None => return,
+        };
+        let message = Message::GameOver {
+            observation,
+            winner,
+        };
+
+        if let Err(e) = self.send(&message) {
+            self.report(&e);
+        }
+    }
+}
+
+/// Writes every line sent over `lines` to the bot, until it can't be
+/// written to anymore.
+fn write_lines(mut stdin: ChildStdin, lines: Receiver<String>) {
+    for line in lines {
+        if stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| stdin.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+impl Drop for External {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,339 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use super::{pick_best, played_card, Bot, Observation};
+use crate::{
+    cards::{Card, CardColor, CardValue, Deck},
+    game::{Action, Game},
+};
+
+/// How many moves a playout makes before it's scored on hand sizes instead.
+const MAX_PLAYOUT: usize = 300;
+
+/// How often a playout plays a random card instead of the greedy one.
+const PLAYOUT_RANDOMNESS: f64 = 0.2;
+
+/// How much the search explores moves it knows little about.
+const EXPLORATION: f64 = 0.7;
+
+/// How long the hard bot thinks about each move.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Budget {
+    /// Plays out this many games. The same seed always plays the same way.
+    Iterations(u32),
+    /// Plays out games until the time is up.
+    Time(Duration),
+}
+
+impl Budget {
+    /// Reads `500` as 500 iterations and `500ms` as half a second.
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.strip_suffix("ms") {
+            Some(millis) => millis
+                .parse()
+                .ok()
+                .map(Duration::from_millis)
+                .map(Budget::Time),
+            None => text.parse().ok().map(Budget::Iterations),
+        }
+    }
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget::Iterations(1000)
+    }
+}
+
+/// A move as far as the search cares: the card that's played and the color
+/// it's played as, or `None` for drawing. Two copies of the same card are
+/// the same move, which lets guesses of other players' hands share a tree.
+type MoveKey = Option<(CardValue, CardColor)>;
+
+fn move_key(hand: &[Card], action: &Action) -> MoveKey {
+    let card = played_card(hand, action)?;
+    let color = match action {
+        Action::UseCard {
+            color: Some(color), ..
+        } => *color,
+        _ => card.color,
+    };
+
+    Some((card.value, color))
+}
+
+struct Node {
+    /// The move that led here and the seat that made it.
+    key: MoveKey,
+    player: u8,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: u32,
+    wins: f64,
+    /// How many times this move could have been picked when its parent was
+    /// visited.
+    available: u32,
+}
+
+/// Information set Monte Carlo tree search. Every iteration guesses the
+/// cards the bot can't see, consistent with what it has seen, and plays a
+/// game out on that guess. Moves that win the most across guesses are
+/// played.
+pub struct Ismcts {
+    rng: StdRng,
+    budget: Budget,
+}
+
+impl Ismcts {
+    pub fn new(seed: u64, budget: Budget) -> Self {
+        Ismcts {
+            rng: StdRng::seed_from_u64(seed),
+            budget,
+        }
+    }
+
+    /// Deals `unseen`, the cards the bot can't see, at random. Where it can,
+    /// other players only get as many cards of a color they drew on, or
+    /// wilds, as they've drawn since.
+    fn sample(&mut self, observation: &Observation, unseen: &[Card]) -> Game {
+        let mut unseen = unseen.to_vec();
+        unseen.shuffle(&mut self.rng);
+
+        let mut hidden = vec![];
+        for (seat, player) in observation.players.iter().enumerate() {
+            if seat == observation.seat as usize {
+                continue;
+            }
+            // Drawing instead of following also means they had no wilds.
+            let mut allowed: Vec<_> = player
+                .voids
+                .iter()
+                .map(|void| (void.color, player.cards.saturating_sub(void.hand_size)))
+                .collect();
+            let matches = |card: &Card, color| card.is_wild() || card.color == color;
+            for _ in 0..player.cards {
+                let index = unseen
+                    .iter()
+                    .position(|card| {
+                        allowed
+                            .iter()
+                            .all(|(color, left)| *left > 0 || !matches(card, *color))
+                    })
+                    .unwrap_or(0);
+                if index < unseen.len() {
+                    let card = unseen.remove(index);
+                    for (color, left) in allowed.iter_mut() {
+                        if matches(&card, *color) {
+                            *left = left.saturating_sub(1);
+                        }
+                    }
+                    hidden.push(card);
+                }
+            }
+        }
+        hidden.extend(unseen);
+
+        Game::from_observation(observation, hidden).with_seed(self.rng.gen())
+    }
+
+    /// Plays like the greedy bot most of the time, and a random card
+    /// otherwise.
+    fn playout_move(&mut self, hand: &[Card], moves: &[Action]) -> Action {
+        let plays = &moves[..moves.len() - 1];
+
+        if self.rng.gen_bool(PLAYOUT_RANDOMNESS) {
+            if let Some(action) = plays.choose(&mut self.rng) {
+                return action.clone();
+            }
+        }
+
+        pick_best(hand, moves, |card| card.points() as i32)
+    }
+
+    /// Plays `game` out and returns the winner, or whoever holds the fewest
+    /// cards if it takes too long.
+    fn playout(&mut self, game: &mut Game) -> u8 {
+        for _ in 0..MAX_PLAYOUT {
+            if let Some(winner) = game.winner() {
+                return winner;
+            }
+            let id = game.current_turn;
+            let moves = game.legal_moves(id);
+            let action = self.playout_move(&game.players[id as usize].cards, &moves);
+            let _ = game.apply(&action);
+        }
+
+        game.winner().unwrap_or_else(|| {
+            game.players
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, player)| player.card_num())
+                .map(|(seat, _)| seat as u8)
+                .unwrap()
+        })
+    }
+
+    fn iterate(&mut self, observation: &Observation, unseen: &[Card], tree: &mut Vec<Node>) {
+        let mut game = self.sample(observation, unseen);
+        let mut node = 0;
+
+        while game.winner().is_none() {
+            let id = game.current_turn;
+            let hand = game.players[id as usize].cards.clone();
+            let mut moves: Vec<(MoveKey, Action)> = vec![];
+            for action in game.legal_moves(id) {
+                let key = move_key(&hand, &action);
+                if !moves.iter().any(|(val, _)| *val == key) {
+                    moves.push((key, action));
+                }
+            }
+
+            for child in tree[node].children.clone() {
+                if moves.iter().any(|(key, _)| *key == tree[child].key) {
+                    tree[child].available += 1;
+                }
+            }
+
+            let untried: Vec<&(MoveKey, Action)> = moves
+                .iter()
+                .filter(|(key, _)| {
+                    !tree[node]
+                        .children
+                        .iter()
+                        .any(|child| tree[*child].key == *key)
+                })
+                .collect();
+
+            if let Some((key, action)) = untried.choose(&mut self.rng).copied() {
+                let _ = game.apply(action);
+                tree.push(Node {
+                    key: *key,
+                    player: id,
+                    parent: Some(node),
+                    children: vec![],
+                    visits: 0,
+                    wins: 0.0,
+                    available: 1,
+                });
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                node = child;
+                break;
+            }
+
+            let ucb = |child: &Node| {
+                child.wins / child.visits as f64
+                    + EXPLORATION * ((child.available as f64).ln() / child.visits as f64).sqrt()
+            };
+            let (child, action) = moves
+                .iter()
+                .filter_map(|(key, action)| {
+                    tree[node]
+                        .children
+                        .iter()
+                        .find(|child| tree[**child].key == *key)
+                        .map(|child| (*child, action))
+                })
+                .max_by(|(a, _), (b, _)| ucb(&tree[*a]).total_cmp(&ucb(&tree[*b])))
+                .unwrap();
+            let _ = game.apply(action);
+            node = child;
+        }
+
+        let winner = self.playout(&mut game);
+
+        let mut current = Some(node);
+        while let Some(index) = current {
+            let node = &mut tree[index];
+            node.visits += 1;
+            if node.player == winner {
+                node.wins += 1.0;
+            }
+            current = node.parent;
+        }
+    }
+}
+
+impl Bot for Ismcts {
+    fn choose(&mut self, observation: &Observation, moves: &[Action]) -> Action {
+        if moves.len() == 1 {
+            return moves[0].clone();
+        }
+
+        let mut tree = vec![Node {
+            key: None,
+            player: observation.seat,
+            parent: None,
+            children: vec![],
+            visits: 0,
+            wins: 0.0,
+            available: 0,
+        }];
+
+        let seen: HashSet<u16> = observation
+            .hand
+            .iter()
+            .chain(observation.discard.iter())
+            .chain(Some(&observation.top_card))
+            .map(|card| card.id)
+            .collect();
+        let unseen: Vec<Card> = Deck::unshuffled()
+            .cards()
+            .iter()
+            .filter(|card| !seen.contains(&card.id))
+            .copied()
+            .collect();
+
+        let start = Instant::now();
+        let mut iterations = 0;
+        while match self.budget {
+            Budget::Iterations(n) => iterations < n,
+            Budget::Time(time) => start.elapsed() < time,
+        } {
+            self.iterate(observation, &unseen, &mut tree);
+            iterations += 1;
+        }
+
+        let best = tree[0]
+            .children
+            .iter()
+            .max_by_key(|child| tree[**child].visits)
+            .map(|child| tree[*child].key);
+
+        best.and_then(|key| {
+            moves
+                .iter()
+                .find(|action| move_key(&observation.hand, action) == key)
+        })
+        .unwrap_or(&moves[moves.len() - 1])
+        .clone()
+    }
+}
@@ -0,0 +1,239 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Computer players. A [`Bot`] is shown what its player can see of the
+//! game and the moves it's allowed to make, and picks one of them.
+
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    cards::{Card, CardColor, CardValue},
+    game::{Action, Event, Game},
+};
+
+mod external;
+mod ismcts;
+pub mod network;
+
+pub use crate::game::{Observation, Seat, Void};
+pub use external::External;
+pub use ismcts::{Budget, Ismcts};
+
+/// The strategies [`strategy`] knows about. `hard` thinks for as long as
+/// its [`Budget`] allows, given after a colon: `hard:5000` plays out 5000
+/// games a move and `hard:500ms` thinks for half a second.
+pub const STRATEGIES: [&str; 4] = ["random", "greedy", "cautious", "hard"];
+
+/// Picks moves for one player. Bots only get to see what their player
+/// could, so the same bot can play in-process or over the network.
+pub trait Bot: Send {
+    /// Picks one of `moves`, the player's legal moves. There's always at
+    /// least one, drawing.
+    fn choose(&mut self, observation: &Observation, moves: &[Action]) -> Action;
+
+    /// Called once someone has won the game.
+    fn game_over(&mut self, _observation: &Observation) {}
+}
+
+/// Keeps track of the colors players drew on instead of following, so
+/// observations can tell bots about them. Whoever runs the game feeds it
+/// the events of every move.
+#[derive(Clone, Debug, Default)]
+pub struct Voids {
+    players: HashMap<String, Vec<Void>>,
+}
+
+impl Voids {
+    /// Notes what `events`, from a move just made in `game`, say about the
+    /// players' hands.
+    pub fn update(&mut self, game: &Game, events: &[Event]) {
+        // Cards dealt on joining and penalties drawn say nothing about a hand.
+        let chose_to_draw = !events
+            .iter()
+            .any(|event| matches!(event, Event::Joined { .. } | Event::PenaltyCleared));
+
+        for event in events {
+            match event {
+                Event::Played { name, .. } => {
+                    self.players.remove(name);
+                }
+                Event::Drew { name, count } if chose_to_draw => {
+                    let cards = game
+                        .player_index(name)
+                        .map_or(0, |id| game.players[id as usize].card_num());
+                    let void = Void {
+                        color: game.last_card.color,
+                        hand_size: cards.saturating_sub(*count as usize),
+                    };
+
+                    // A later draw on the same color tells more.
+                    let voids = self.players.entry(name.clone()).or_default();
+                    voids.retain(|val| val.color != void.color);
+                    voids.push(void);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// What the player at `id` can see of `game`, with the colors everyone
+    /// drew on.
+    pub fn observe(&self, game: &Game, id: u8) -> Observation {
+        let mut observation = Observation::new(game, id);
+        for seat in observation.players.iter_mut() {
+            if let Some(voids) = self.players.get(&seat.name) {
+                seat.voids = voids.clone();
+            }
+        }
+
+        observation
+    }
+}
+
+/// The bot playing `name`, one of [`STRATEGIES`]. Bots that roll dice use
+/// `seed`, so the same seed always plays the same way.
+pub fn strategy(name: &str, seed: u64) -> Option<Box<dyn Bot>> {
+    match name.split_once(':') {
+        Some(("hard", budget)) => Some(Box::new(Ismcts::new(seed, Budget::parse(budget)?))),
+        Some(_) => None,
+        None => match name {
+            "random" => Some(Box::new(Random::new(seed))),
+            "greedy" => Some(Box::new(Greedy)),
+            "cautious" => Some(Box::new(Cautious)),
+            "hard" => Some(Box::new(Ismcts::new(seed, Budget::default()))),
+            _ => None,
+        },
+    }
+}
+
+/// The card from `hand` a move plays, if it plays one.
+pub fn played_card(hand: &[Card], action: &Action) -> Option<Card> {
+    match action {
+        Action::UseCard { card_id, .. } => hand.iter().find(|card| card.id == *card_id).copied(),
+        _ => None,
+    }
+}
+
+/// The color there are the most cards of in `hand`, not counting the card
+/// with `card_id`.
+pub fn best_color(hand: &[Card], card_id: u16) -> CardColor {
+    CardColor::ALL
+        .iter()
+        .copied()
+        .max_by_key(|color| {
+            hand.iter()
+                .filter(|card| card.id != card_id && card.color == *color)
+                .count()
+        })
+        .unwrap()
+}
+
+/// Whether `action` plays a wild as anything other than the player's best
+/// color. Bots that care about colors skip those.
+fn wrong_color(hand: &[Card], action: &Action) -> bool {
+    match action {
+        Action::UseCard {
+            card_id,
+            color: Some(color),
+            ..
+        } => *color != best_color(hand, *card_id),
+        _ => false,
+    }
+}
+
+/// Picks the move with the highest `score`, playing wilds as the best color
+/// when it can. Ties go to the move listed first.
+fn pick_best<F: Fn(&Card) -> i32>(hand: &[Card], moves: &[Action], score: F) -> Action {
+    let mut best: Option<(&Action, i32)> = None;
+
+    for action in moves {
+        let card = match played_card(hand, action) {
+            Some(card) => card,
+            None => continue,
+        };
+        if wrong_color(hand, action) {
+            continue;
+        }
+        let value = score(&card);
+        if best.is_none_or(|(_, best)| value > best) {
+            best = Some((action, value));
+        }
+    }
+
+    // A wild whose best color can't go on a +2 is still worth playing in
+    // some other color.
+    let fallback = moves
+        .iter()
+        .find(|action| played_card(hand, action).is_some());
+
+    best.map(|(action, _)| action)
+        .or(fallback)
+        .unwrap_or(&moves[moves.len() - 1])
+        .clone()
+}
+
+/// Plays any legal move, drawing included.
+pub struct Random {
+    rng: StdRng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random {
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Bot for Random {
+    fn choose(&mut self, _observation: &Observation, moves: &[Action]) -> Action {
+        moves.choose(&mut self.rng).unwrap().clone()
+    }
+}
+
+/// Gets rid of the cards worth the most points first, and only draws when
+/// there's nothing to play.
+pub struct Greedy;
+
+impl Bot for Greedy {
+    fn choose(&mut self, observation: &Observation, moves: &[Action]) -> Action {
+        pick_best(&observation.hand, moves, |card| card.points() as i32)
+    }
+}
+
+/// Plays numbers first and keeps wilds for when nothing else goes.
+pub struct Cautious;
+
+impl Bot for Cautious {
+    fn choose(&mut self, observation: &Observation, moves: &[Action]) -> Action {
+        pick_best(&observation.hand, moves, |card| match card.value {
+            CardValue::Num(n) => 100 + n as i32,
+            CardValue::Skip | CardValue::Reverse | CardValue::PlusTwo => 50,
+            CardValue::Wild => 10,
+            CardValue::WildPlusFour => 0,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{thread, time::Duration};
+
+use super::Bot;
+use crate::client::Client;
+
+/// Sits `bot` down as `name` at the game `client` talks to and plays its
+/// turns, checking every `poll` whether it's up, until someone wins.
+/// Returns the winner's name.
+///
+/// When the server rejects one of the bot's moves, `rejected` is told why
+/// and the bot draws instead.
+pub fn play<F: FnMut(&str)>(
+    client: &Client,
+    name: &str,
+    bot: &mut dyn Bot,
+    poll: Duration,
+    mut rejected: F,
+) -> Result<String, String> {
+    client.join(name)?;
+
+    loop {
+        // Joining gets no answer, so this also gives the server time to seat
+        // the bot before it asks what it can see.
+        thread::sleep(poll);
+
+        let observation = client.observe(name)?;
+        if let Some(winner) = observation.winner() {
+            bot.game_over(&observation);
+            return Ok(winner.to_string());
+        }
+        if observation.players.len() < 2 || observation.current_turn != observation.seat {
+            continue;
+        }
+
+        let moves = client.legal_moves(name)?;
+        let draw = match moves.last() {
+            Some(draw) => draw,
+            None => continue,
+        };
+
+        let action = bot.choose(&observation, &moves);
+        if let Err(e) = client.submit(&action) {
+            rejected(&e);
+            // If drawing fails too, the next look at the game says why.
+            let _ = client.submit(draw);
+        }
+    }
+}
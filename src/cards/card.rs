@@ -22,8 +22,10 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+/// A card's color. Wilds are `None` until a color is picked for them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub enum CardColor {
     Red,
     Green,
@@ -32,7 +34,18 @@ pub enum CardColor {
     None,
 }
 
-#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+impl CardColor {
+    /// The colors a card can be played as.
+    pub const ALL: [CardColor; 4] = [
+        CardColor::Red,
+        CardColor::Green,
+        CardColor::Blue,
+        CardColor::Yellow,
+    ];
+}
+
+/// What's printed on a card.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub enum CardValue {
     Num(u8),
     Reverse,
@@ -42,30 +55,77 @@ pub enum CardValue {
     WildPlusFour,
 }
 
+/// A single card from the deck.
 #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub struct Card {
     pub value: CardValue,
     pub color: CardColor,
+    /// Tells copies of the same card apart. Every card in a deck has its own.
+    #[serde(default)]
+    pub id: u16,
 }
 
-impl ToString for CardValue {
-    fn to_string(&self) -> String {
+impl fmt::Display for CardValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            CardValue::Num(n) => n.to_string(),
-            CardValue::PlusTwo => String::from("+2"),
-            CardValue::Reverse => String::from("🔃"),
-            CardValue::Skip => String::from("⛔"),
-            CardValue::Wild => String::from("Wild"),
-            CardValue::WildPlusFour => String::from("+4"),
+            CardValue::Num(n) => write!(f, "{}", n),
+            CardValue::PlusTwo => f.write_str("+2"),
+            CardValue::Reverse => f.write_str("🔃"),
+            CardValue::Skip => f.write_str("⛔"),
+            CardValue::Wild => f.write_str("Wild"),
+            CardValue::WildPlusFour => f.write_str("+4"),
         }
     }
 }
 
 impl Card {
+    /// A card with id 0. [`Deck`](super::Deck) numbers the cards it builds.
     pub fn new(value: CardValue, color: CardColor) -> Self {
-        Card { value, color }
+        Card {
+            value,
+            color,
+            id: 0,
+        }
+    }
+
+    /// What the card is worth to the winner when it's left in a hand.
+    pub fn points(&self) -> u32 {
+        match self.value {
+            CardValue::Num(n) => n as u32,
+            CardValue::Reverse | CardValue::PlusTwo | CardValue::Skip => 20,
+            CardValue::Wild | CardValue::WildPlusFour => 50,
+        }
+    }
+
+    /// Whether the card is a wild or a wild +4.
+    pub fn is_wild(&self) -> bool {
+        self.value == CardValue::Wild || self.value == CardValue::WildPlusFour
+    }
+
+    /// Whether the card can go on `top` while `plus` cards are waiting to
+    /// be drawn. Wilds need a color picked first.
+    pub fn can_play_on(&self, top: &Card, plus: u8) -> bool {
+        if plus != 0 {
+            return (self.value == CardValue::WildPlusFour || self.value == CardValue::PlusTwo)
+                && (top.value == self.value || top.color == self.color);
+        }
+
+        if self.is_wild() && self.color != CardColor::None {
+            return true;
+        }
+
+        top.value == self.value || top.color == self.color
+    }
+
+    /// Picks the color a wild card is played as. Other cards keep theirs.
+    pub fn set_color(&mut self, color: CardColor) {
+        if self.is_wild() && color != CardColor::None {
+            self.color = color;
+        }
     }
 
+    /// Moves a wild card to the next color, in the order red, green, blue,
+    /// yellow.
     pub fn cycle_colors_up(&mut self) {
         if !(self.value == CardValue::Wild || self.value == CardValue::WildPlusFour) {
             return;
@@ -89,6 +149,7 @@ impl Card {
         }
     }
 
+    /// Moves a wild card to the previous color.
     pub fn cycle_colors_down(&mut self) {
         if !(self.value == CardValue::Wild || self.value == CardValue::WildPlusFour) {
             return;
@@ -116,6 +177,7 @@ impl Card {
         Card {
             value: CardValue::Skip,
             color,
+            id: 0,
         }
     }
 
@@ -123,6 +185,7 @@ impl Card {
         Card {
             value: CardValue::Reverse,
             color,
+            id: 0,
         }
     }
 
@@ -130,6 +193,7 @@ impl Card {
         Card {
             value: CardValue::PlusTwo,
             color,
+            id: 0,
         }
     }
 
@@ -137,6 +201,7 @@ impl Card {
         Card {
             value: CardValue::Wild,
             color: CardColor::None,
+            id: 0,
         }
     }
 
@@ -144,6 +209,7 @@ impl Card {
         Card {
             value: CardValue::WildPlusFour,
             color: CardColor::None,
+            id: 0,
         }
     }
 }
@@ -24,8 +24,11 @@ SOFTWARE.
 
 use super::card::*;
 use crate::player::Player;
-use rand::seq::SliceRandom;
+use rand::{rngs::StdRng, seq::SliceRandom, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 
+/// The draw pile. Iterating over it deals the cards from the top.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Deck {
     cards: Vec<Card>,
 }
@@ -44,8 +47,37 @@ impl ExactSizeIterator for Deck {
     }
 }
 
+impl Default for Deck {
+    fn default() -> Self {
+        Deck::new()
+    }
+}
+
 impl Deck {
+    /// Builds a full, shuffled deck.
     pub fn new() -> Self {
+        let mut result = Deck::unshuffled();
+        result.shuffle();
+
+        result
+    }
+
+    /// Builds a deck whose order only depends on `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        Deck::with_rng(&mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Builds a full deck shuffled with `rng`.
+    pub fn with_rng<R: RngCore>(rng: &mut R) -> Self {
+        let mut result = Deck::unshuffled();
+        result.shuffle_with(rng);
+
+        result
+    }
+
+    /// Builds the full 108 card deck in a fixed order: the numbers, then the
+    /// action cards, then the wilds. Cards are numbered in that order.
+    pub fn unshuffled() -> Self {
         use CardColor::*;
         let mut result = Vec::<Card>::new();
         let colors = [Red, Green, Blue, Yellow];
@@ -54,7 +86,10 @@ impl Deck {
             for color in colors {
                 let card = Card::new(CardValue::Num(i), color);
                 result.push(card);
-                result.push(card);
+                // There's only one 0 of each color.
+                if i != 0 {
+                    result.push(card);
+                }
             }
         }
 
@@ -74,24 +109,44 @@ impl Deck {
             result.push(Card::wild_plus_four());
         }
 
-        let mut result = Deck { cards: result };
-        result.shuffle();
+        for (id, card) in result.iter_mut().enumerate() {
+            card.id = id as u16;
+        }
 
-        result
+        Deck { cards: result }
+    }
+
+    /// Builds a deck that deals `cards` in the given order, without shuffling.
+    pub fn from_cards(cards: Vec<Card>) -> Self {
+        Deck { cards }
+    }
+
+    /// The remaining cards, in the order they will be dealt.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
     }
 
+    /// Deals the top card to `player`, if there's one left.
     pub fn give_card(&mut self, player: &mut Player) {
-        player.add_card(self.take_card().unwrap());
+        if let Some(card) = self.take_card() {
+            player.add_card(card);
+        }
     }
 
     pub fn shuffle(&mut self) {
-        self.cards.shuffle(&mut rand::thread_rng());
+        self.shuffle_with(&mut rand::thread_rng());
+    }
+
+    pub fn shuffle_with<R: RngCore>(&mut self, rng: &mut R) {
+        self.cards.shuffle(rng);
     }
 
+    /// Puts `card` at the bottom of the deck.
     pub fn return_card(&mut self, card: Card) {
         self.cards.push(card);
     }
 
+    /// Takes the top card, if there's one left.
     pub fn take_card(&mut self) -> Option<Card> {
         if !self.cards.is_empty() {
             Some(self.cards.remove(0))
@@ -22,13 +22,20 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
 
-use serde_json::json;
+use serde::de::DeserializeOwned;
 
-use crate::cards::Card;
+use crate::cards::{Card, CardColor};
+use crate::game::{Action, Direction, Observation, RuleError};
 use crate::player::Player;
+use crate::protocol::Request;
+use crate::stats::Stats;
+
+/// How long to wait for the server to answer a request.
+const TIMEOUT: Duration = Duration::from_secs(3);
 
 pub struct Client {
     ip: String,
@@ -37,205 +44,251 @@ impl Client {
     pub fn new(ip: String) -> Self {
         Client { ip }
     }
-    pub fn join(&self, name: &str) -> Result<(), String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "join",
-                    "name": name,
-                })
-                .to_string();
-                stream.write_all(request.as_bytes()).unwrap();
-
-                Ok(())
-            }
-            Err(_) => Err(String::from("Faild connecting")),
-        }
+
+    /// The first address the server's address resolves to.
+    fn address(&self) -> Result<SocketAddr, String> {
+        self.ip
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .ok_or_else(|| String::from("invalid address"))
     }
 
-    pub fn take_card(&self, name: &str, num: u8) -> Result<(), String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "take_cards",
-                    "name": name,
-                    "num": num
-                })
-                .to_string();
+    /// Opens a connection and sends `request` over it. Gives up if the
+    /// server doesn't answer within [`TIMEOUT`].
+    fn send(&self, request: Request) -> Result<TcpStream, String> {
+        let mut stream = TcpStream::connect_timeout(&self.address()?, TIMEOUT)
+            .map_err(|_| String::from("Failed connecting"))?;
 
-                stream.write_all(request.as_bytes()).unwrap();
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        stream
+            .write_all(&serde_json::to_vec(&request).unwrap())
+            .map_err(|e| e.to_string())?;
 
-                Ok(())
-            }
-            Err(_) => Err(String::from("Failed connecting")),
-        }
+        Ok(stream)
     }
 
-    pub fn get_cards_for(&self, name: &str) -> Result<Vec<crate::cards::Card>, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "get_cards",
-                    "name": name,
-                })
-                .to_string();
+    /// Reads a JSON response, however many reads it takes.
+    fn read_json<T: DeserializeOwned>(stream: TcpStream) -> Result<T, String> {
+        let mut responses = serde_json::Deserializer::from_reader(stream).into_iter();
 
-                stream.write_all(request.as_bytes()).unwrap();
-                let mut buf = [0u8; 2048];
+        match responses.next() {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Err(String::from("No response")),
+        }
+    }
 
-                let size = stream.read(&mut buf).unwrap();
+    fn read_byte(mut stream: TcpStream) -> Result<u8, String> {
+        let mut buf = [0u8];
+        stream.read_exact(&mut buf).map_err(|e| e.to_string())?;
 
-                let cards: Vec<crate::cards::Card> = serde_json::from_slice(&buf[0..size]).unwrap();
+        Ok(buf[0])
+    }
 
-                Ok(cards)
-            }
-            Err(_) => Err(String::from("Failed connecting")),
+    /// Makes sure there's a server listening on the address.
+    pub fn check(&self) -> Result<(), String> {
+        match TcpStream::connect_timeout(&self.address()?, TIMEOUT) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(match e.kind() {
+                ErrorKind::ConnectionRefused => String::from("connection refused"),
+                ErrorKind::TimedOut => String::from("connection timed out"),
+                _ => e.to_string(),
+            }),
         }
     }
 
+    pub fn join(&self, name: &str) -> Result<(), String> {
+        self.send(Request::Join {
+            name: name.to_string(),
+        })?;
+
+        Ok(())
+    }
+
+    pub fn get_cards_for(&self, name: &str) -> Result<Vec<Card>, String> {
+        let stream = self.send(Request::GetCards {
+            name: name.to_string(),
+        })?;
+
+        Client::read_json(stream)
+    }
+
     pub fn get_players(&self) -> Result<Vec<Player>, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "get_players",
-                })
-                .to_string();
+        let stream = self.send(Request::GetPlayers)?;
 
-                stream.write_all(request.as_bytes()).unwrap();
-                let mut buf = [0; 2048];
+        Client::read_json(stream)
+    }
 
-                let size = stream.read(&mut buf).unwrap();
+    pub fn get_stats(&self) -> Result<Stats, String> {
+        let stream = self.send(Request::GetStats)?;
 
-                let players = serde_json::from_slice(&buf[0..size]).unwrap();
+        Client::read_json(stream)
+    }
 
-                Ok(players)
-            }
-            Err(_) => Err(String::from("Failed connecting")),
+    /// Reads the answer to a move. A rejected move's error says why.
+    fn read_move(stream: TcpStream) -> Result<(), String> {
+        match Client::read_byte(stream)? {
+            0 => Ok(()),
+            code => Err(RuleError::from_code(code).map_or_else(
+                || String::from("The server rejected the move"),
+                |e| e.to_string(),
+            )),
         }
     }
 
-    pub fn use_card(&self, name: &str, card_index: usize) -> Result<(), u8> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "use_card",
-                    "name": name,
-                    "card_index": card_index,
-                })
-                .to_string();
+    /// Plays a card. A rejected play's error says why.
+    pub fn use_card(&self, name: &str, card_id: u16) -> Result<(), String> {
+        let stream = self.send(Request::UseCard {
+            name: name.to_string(),
+            card_id,
+            color: None,
+        })?;
 
-                stream.write_all(request.as_bytes()).unwrap();
+        Client::read_move(stream)
+    }
 
-                let mut buf = [0u8];
-                stream.read_exact(&mut buf).unwrap();
+    /// Draws the penalty, or one card if there isn't one, ending the turn.
+    pub fn draw(&self, name: &str) -> Result<(), String> {
+        let stream = self.send(Request::Draw {
+            name: name.to_string(),
+        })?;
 
-                if buf[0] == 0 {
-                    Ok(())
-                } else {
-                    Err(buf[0])
-                }
-            }
-            Err(_) => Err(4),
-        }
+        Client::read_move(stream)
     }
 
-    pub fn current_turn(&self) -> Result<String, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "current_turn",
-                })
-                .to_string();
+    /// What `name` can see of the game.
+    pub fn observe(&self, name: &str) -> Result<Observation, String> {
+        let stream = self.send(Request::Observe {
+            name: name.to_string(),
+        })?;
+
+        Client::read_json::<Option<Observation>>(stream)?
+            .ok_or_else(|| RuleError::UnknownPlayer.to_string())
+    }
 
-                stream.write_all(request.as_bytes()).unwrap();
+    /// Every move `name` is allowed to make right now.
+    pub fn legal_moves(&self, name: &str) -> Result<Vec<Action>, String> {
+        let stream = self.send(Request::LegalMoves {
+            name: name.to_string(),
+        })?;
 
-                let mut buf = [0u8; 128];
-                let size = stream.read(&mut buf).unwrap();
+        Client::read_json(stream)
+    }
 
-                Ok(String::from_utf8(buf[0..size].to_vec()).unwrap())
+    /// Makes any move. Rejected plays and draws say why.
+    pub fn submit(&self, action: &Action) -> Result<(), String> {
+        match action {
+            Action::Join { name } => self.join(name),
+            Action::UseCard {
+                name,
+                card_id,
+                color,
+            } => {
+                let stream = self.send(Request::UseCard {
+                    name: name.clone(),
+                    card_id: *card_id,
+                    color: *color,
+                })?;
+
+                Client::read_move(stream)
             }
-            Err(_) => Err(String::from("Failed connecting")),
+            Action::Draw { name } => self.draw(name),
+            Action::CycleColorUp { name, card_id } => self.cycle_color_up(name, *card_id),
+            Action::CycleColorDown { name, card_id } => self.cycle_color_down(name, *card_id),
+            Action::SetColor {
+                name,
+                card_id,
+                color,
+            } => self.set_color(name, *card_id, *color),
         }
     }
 
-    pub fn get_plus(&self) -> Result<u8, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "get_plus",
-                })
-                .to_string();
+    /// The ids of the cards `name` could play on the discard pile, whether
+    /// or not it's their turn.
+    pub fn playable_cards(&self, name: &str) -> Result<Vec<u16>, String> {
+        let stream = self.send(Request::PlayableCards {
+            name: name.to_string(),
+        })?;
 
-                stream.write_all(request.as_bytes()).unwrap();
+        Client::read_json(stream)
+    }
 
-                let mut buf = [0u8];
-                stream.read_exact(&mut buf).unwrap();
+    pub fn current_turn(&self) -> Result<String, String> {
+        let mut stream = self.send(Request::CurrentTurn)?;
 
-                Ok(buf[0])
-            }
-            Err(_) => Err(String::from("Failed connecting")),
-        }
+        let mut buf = [0u8; 128];
+        let size = stream.read(&mut buf).map_err(|e| e.to_string())?;
+
+        String::from_utf8(buf[0..size].to_vec()).map_err(|e| e.to_string())
     }
 
-    pub fn reset_plus(&self) -> Result<u8, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "reset_plus",
-                })
-                .to_string();
+    pub fn next_player(&self) -> Result<String, String> {
+        let mut stream = self.send(Request::NextPlayer)?;
 
-                stream.write_all(request.as_bytes()).unwrap();
+        let mut buf = [0u8; 128];
+        let size = stream.read(&mut buf).map_err(|e| e.to_string())?;
 
-                Ok(0)
-            }
-            Err(_) => Err(String::from("Failed connecting")),
-        }
+        String::from_utf8(buf[0..size].to_vec()).map_err(|e| e.to_string())
+    }
+
+    pub fn get_direction(&self) -> Result<Direction, String> {
+        let stream = self.send(Request::GetDirection)?;
+
+        Client::read_json(stream)
     }
-    pub fn top_card(&self) -> Result<Card, String> {
-        match TcpStream::connect(&self.ip[..]) {
-            Ok(mut stream) => {
-                let request: String = json!({
-                    "request_type": "top_card",
-                })
-                .to_string();
 
-                stream.write_all(request.as_bytes()).unwrap();
+    pub fn get_plus(&self) -> Result<u8, String> {
+        let stream = self.send(Request::GetPlus)?;
 
-                let mut buf = [0u8; 128];
-                let size = stream.read(&mut buf).unwrap();
+        Client::read_byte(stream)
+    }
 
-                let card: Card = serde_json::from_slice(&buf[0..size]).unwrap();
+    pub fn top_card(&self) -> Result<Card, String> {
+        let stream = self.send(Request::TopCard)?;
 
-                Ok(card)
-            }
-            Err(_) => Err(String::from("Failed connecting")),
-        }
+        Client::read_json(stream)
     }
 
-    pub fn cycle_color_down(&self, name: &str, card_index: usize) {
-        if let Ok(mut stream) = TcpStream::connect(&self.ip[..]) {
-            let request: String = json!({
-                "request_type": "cycle_color_down",
-                "name": name,
-                "card_index": card_index
-            })
-            .to_string();
+    pub fn save(&self) -> Result<(), String> {
+        let stream = self.send(Request::Save)?;
 
-            stream.write_all(request.as_bytes()).unwrap();
+        match Client::read_byte(stream)? {
+            0 => Ok(()),
+            1 => Err(String::from("The server has no save file")),
+            _ => Err(String::from(
+                "The server couldn't write its save file, see its log",
+            )),
         }
     }
 
-    pub fn cycle_color_up(&self, name: &str, card_index: usize) {
-        if let Ok(mut stream) = TcpStream::connect(&self.ip[..]) {
-            let request: String = json!({
-                "request_type": "cycle_color_up",
-                "name": name,
-                "card_index": card_index
-            })
-            .to_string();
+    pub fn set_color(&self, name: &str, card_id: u16, color: CardColor) -> Result<(), String> {
+        self.send(Request::SetColor {
+            name: name.to_string(),
+            card_id,
+            color,
+        })?;
 
-            stream.write_all(request.as_bytes()).unwrap();
-        }
+        Ok(())
+    }
+
+    pub fn cycle_color_down(&self, name: &str, card_id: u16) -> Result<(), String> {
+        self.send(Request::CycleColorDown {
+            name: name.to_string(),
+            card_id,
+        })?;
+
+        Ok(())
+    }
+
+    pub fn cycle_color_up(&self, name: &str, card_id: u16) -> Result<(), String> {
+        self.send(Request::CycleColorUp {
+            name: name.to_string(),
+            card_id,
+        })?;
+
+        Ok(())
     }
 }
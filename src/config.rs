@@ -0,0 +1,235 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{collections::HashMap, env, fs, io::ErrorKind, path::PathBuf};
+
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::ui::ThemeName;
+
+/// Everything a player can do from the game screen with a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyAction {
+    Left,
+    Right,
+    ColorUp,
+    ColorDown,
+    Play,
+    Hint,
+    Draw,
+    Save,
+    Sort,
+    Controls,
+    Quit,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 11] = [
+        KeyAction::Left,
+        KeyAction::Right,
+        KeyAction::ColorUp,
+        KeyAction::ColorDown,
+        KeyAction::Play,
+        KeyAction::Hint,
+        KeyAction::Draw,
+        KeyAction::Save,
+        KeyAction::Sort,
+        KeyAction::Controls,
+        KeyAction::Quit,
+    ];
+
+    fn default_keys(self) -> Vec<KeyCode> {
+        match self {
+            KeyAction::Left => vec![KeyCode::Left],
+            KeyAction::Right => vec![KeyCode::Right],
+            KeyAction::ColorUp => vec![KeyCode::Up],
+            KeyAction::ColorDown => vec![KeyCode::Down],
+            KeyAction::Play => vec![KeyCode::Enter],
+            KeyAction::Draw => vec![KeyCode::Char('z')],
+            KeyAction::Save => vec![KeyCode::Char('s')],
+            KeyAction::Sort => vec![KeyCode::Char('o')],
+            KeyAction::Hint => vec![KeyCode::Char('h')],
+            KeyAction::Controls => vec![KeyCode::Char('?')],
+            KeyAction::Quit => vec![KeyCode::Esc],
+        }
+    }
+}
+
+/// The settings read from `config.json` in the user's config directory.
+/// Anything left out keeps its default.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Name filled in on the join screen.
+    pub name: Option<String>,
+    /// Server address used when `-a` isn't given.
+    pub server: Option<String>,
+    /// Tick rate used when `-t` isn't given.
+    pub ticks: Option<u64>,
+    /// Card colors used when `--theme` isn't given.
+    pub theme: Option<ThemeName>,
+    /// Keys for each action, e.g. `"draw": ["z", "d"]`.
+    pub keys: HashMap<KeyAction, Vec<String>>,
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/uno-tui/config.json`, falling back to `~/.config`,
+    /// or `%APPDATA%` on Windows.
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|val| !val.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+
+        Some(dir.join("uno-tui").join("config.json"))
+    }
+
+    /// Loads the config file, using the defaults if there isn't one.
+    pub fn load(path: &PathBuf) -> Result<Self, String> {
+        match fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| format!("Invalid config {}: {}", path.display(), e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Failed reading {}: {}", path.display(), e)),
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+
+    let code = match &name.to_lowercase()[..] {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        other => match other.strip_prefix('f').map(str::parse) {
+            Some(Ok(n)) if (1..=12).contains(&n) => KeyCode::F(n),
+            _ => return Err(format!("Unknown key \"{}\"", name)),
+        },
+    };
+
+    Ok(code)
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => String::from("Space"),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
+
+/// The keys bound to every action on the game screen.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: Vec<(KeyAction, Vec<KeyCode>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: KeyAction::ALL
+                .iter()
+                .map(|action| (*action, action.default_keys()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// The default keymap with the actions set in `keys` rebound. A key can
+    /// only be bound to one action.
+    pub fn new(keys: &HashMap<KeyAction, Vec<String>>) -> Result<Self, String> {
+        let mut result = Keymap::default();
+
+        for (action, codes) in result.bindings.iter_mut() {
+            if let Some(names) = keys.get(action) {
+                *codes = names
+                    .iter()
+                    .map(|name| parse_key(name))
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        for (index, (action, codes)) in result.bindings.iter().enumerate() {
+            for (other, other_codes) in result.bindings[index + 1..].iter() {
+                if let Some(code) = codes.iter().find(|code| other_codes.contains(code)) {
+                    let key = match code {
+                        KeyCode::Char(c) if *c != ' ' => c.to_string(),
+                        _ => key_name(*code),
+                    };
+                    return Err(format!(
+                        "\"{}\" is bound to both {} and {}",
+                        key,
+                        serde_json::to_string(action).unwrap(),
+                        serde_json::to_string(other).unwrap()
+                    ));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn action(&self, code: KeyCode) -> Option<KeyAction> {
+        self.bindings
+            .iter()
+            .find(|(_, codes)| codes.contains(&code))
+            .map(|(action, _)| *action)
+    }
+
+    /// The keys bound to `action`, the way the controls pane shows them.
+    pub fn keys(&self, action: KeyAction) -> String {
+        self.bindings
+            .iter()
+            .find(|(val, _)| *val == action)
+            .map(|(_, codes)| {
+                codes
+                    .iter()
+                    .map(|code| key_name(*code))
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,147 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, Socket, Type};
+
+/// The UDP port servers announce themselves on.
+pub const DISCOVERY_PORT: u16 = 48080;
+
+/// How often a server announces itself.
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Servers that haven't been heard from for this long are dropped.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(4);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Announcement {
+    room: String,
+    port: u16,
+    players: usize,
+}
+
+/// A server found on the local network.
+#[derive(Clone, Debug)]
+pub struct LanServer {
+    pub room: String,
+    pub address: String,
+    pub players: usize,
+    last_seen: Instant,
+}
+
+/// Broadcasts the game server listening on `port` to the local network
+/// until the process exits.
+pub fn announce<F>(room: String, port: u16, players: F) -> Result<(), String>
+where
+    F: Fn() -> usize + Send + 'static,
+{
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .map_err(|e| format!("Couldn't open the discovery socket: {}", e))?;
+    socket
+        .set_broadcast(true)
+        .map_err(|e| format!("Couldn't broadcast on the discovery socket: {}", e))?;
+
+    thread::spawn(move || loop {
+        let announcement = Announcement {
+            room: room.clone(),
+            port,
+            players: players(),
+        };
+        let data = serde_json::to_vec(&announcement).unwrap();
+
+        let _ = socket.send_to(&data, (Ipv4Addr::BROADCAST, DISCOVERY_PORT));
+        thread::sleep(ANNOUNCE_INTERVAL);
+    });
+
+    Ok(())
+}
+
+/// Listens for servers announcing themselves.
+pub struct Discovery {
+    socket: UdpSocket,
+    servers: Vec<LanServer>,
+}
+
+impl Discovery {
+    pub fn new() -> Result<Self, String> {
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))
+            .map_err(|e| e.to_string())?;
+
+        // Several clients on the same machine should all hear the servers.
+        socket.set_reuse_address(true).map_err(|e| e.to_string())?;
+        #[cfg(unix)]
+        socket.set_reuse_port(true).map_err(|e| e.to_string())?;
+
+        let address = SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT);
+        socket.bind(&address.into()).map_err(|e| e.to_string())?;
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        Ok(Discovery {
+            socket: socket.into(),
+            servers: vec![],
+        })
+    }
+
+    /// Reads every pending announcement and forgets servers that went quiet.
+    pub fn poll(&mut self) {
+        let mut buf = [0u8; 512];
+
+        while let Ok((size, source)) = self.socket.recv_from(&mut buf) {
+            if let Ok(announcement) = serde_json::from_slice::<Announcement>(&buf[0..size]) {
+                self.update(announcement, source);
+            }
+        }
+
+        self.servers
+            .retain(|server| server.last_seen.elapsed() < SERVER_TIMEOUT);
+    }
+
+    fn update(&mut self, announcement: Announcement, source: SocketAddr) {
+        let address = format!("{}:{}", source.ip(), announcement.port);
+
+        match self.servers.iter_mut().find(|val| val.address == address) {
+            Some(server) => {
+                server.room = announcement.room;
+                server.players = announcement.players;
+                server.last_seen = Instant::now();
+            }
+            None => self.servers.push(LanServer {
+                room: announcement.room,
+                address,
+                players: announcement.players,
+                last_seen: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn servers(&self) -> &[LanServer] {
+        &self.servers
+    }
+}
@@ -0,0 +1,287 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! A reinforcement learning environment: one agent plays against bots, with
+//! the game encoded as fixed-size arrays of numbers.
+
+use crate::{
+    bot::{self, Bot, Voids},
+    cards::{Card, CardColor, CardValue, Deck},
+    game::{Action, Direction, Game, RuleError},
+};
+
+/// Every kind of card: 13 per color, then the wild and the wild +4.
+pub const CARD_TYPES: usize = 54;
+
+/// Opponents the observation has room for. Tables are at most this big,
+/// plus the agent.
+pub const MAX_OPPONENTS: usize = 9;
+
+/// The length of an observation: the agent's hand counted by card type, the
+/// top card's type and color, every opponent's hand size, the direction and
+/// the penalty waiting to be drawn.
+pub const OBSERVATION_SIZE: usize = CARD_TYPES * 2 + 4 + MAX_OPPONENTS + 2;
+
+/// The number of actions: playing each colored card type, playing a wild
+/// or a wild +4 as each color, and drawing.
+pub const ACTIONS: usize = 52 + 4 + 4 + 1;
+
+/// The action that draws.
+pub const DRAW: usize = ACTIONS - 1;
+
+/// How long a game goes on before it's called off as a draw.
+const MAX_TURNS: u32 = 1000;
+
+fn color_index(color: CardColor) -> Option<usize> {
+    CardColor::ALL.iter().position(|val| *val == color)
+}
+
+/// Where a card's kind is counted in the observation.
+pub fn card_type(card: &Card) -> usize {
+    let value = match card.value {
+        CardValue::Num(n) => n as usize,
+        CardValue::Skip => 10,
+        CardValue::Reverse => 11,
+        CardValue::PlusTwo => 12,
+        CardValue::Wild => return 52,
+        CardValue::WildPlusFour => return 53,
+    };
+
+    color_index(card.color).unwrap_or(0) * 13 + value
+}
+
+/// Which action plays `card`, as `color` if it's a wild.
+fn action_index(card: &Card, color: CardColor) -> Option<usize> {
+    match card.value {
+        CardValue::Wild => color_index(color).map(|index| 52 + index),
+        CardValue::WildPlusFour => color_index(color).map(|index| 56 + index),
+        _ => Some(card_type(card)),
+    }
+}
+
+/// The agent's side of a game against bots. The agent always sits first.
+pub struct UnoEnv {
+    opponents: Vec<String>,
+    bots: Vec<Box<dyn Bot>>,
+    game: Game,
+    voids: Voids,
+    turns: u32,
+}
+
+impl UnoEnv {
+    /// An environment where the agent plays against one bot per strategy in
+    /// `opponents`, see [`bot::STRATEGIES`].
+    pub fn new(opponents: &[&str]) -> Result<Self, String> {
+        if opponents.is_empty() || opponents.len() > MAX_OPPONENTS {
+            return Err(format!("Expected 1 to {} opponents", MAX_OPPONENTS));
+        }
+        if let Some(name) = opponents
+            .iter()
+            .find(|name| bot::strategy(name, 0).is_none())
+        {
+            return Err(format!("Unknown strategy \"{}\"", name));
+        }
+
+        let mut result = UnoEnv {
+            opponents: opponents.iter().map(|name| name.to_string()).collect(),
+            bots: vec![],
+            game: Game::from_deck(Deck::unshuffled()),
+            voids: Voids::default(),
+            turns: 0,
+        };
+        result.reset(0);
+
+        Ok(result)
+    }
+
+    /// Starts a new game. The same seed always deals and plays the same
+    /// way. Returns the agent's first observation.
+    pub fn reset(&mut self, seed: u64) -> [f32; OBSERVATION_SIZE] {
+        self.game = Game::from_deck(Deck::with_seed(seed)).with_seed(seed);
+        self.voids = Voids::default();
+        self.turns = 0;
+        self.bots = self
+            .opponents
+            .iter()
+            .enumerate()
+            .map(|(index, name)| bot::strategy(name, seed ^ (index as u64 + 1)).unwrap())
+            .collect();
+
+        let _ = self.apply(&Action::Join {
+            name: String::from("agent"),
+        });
+        for index in 0..self.opponents.len() {
+            let name = format!("{} {}", index + 1, self.opponents[index]);
+            let _ = self.apply(&Action::Join { name });
+        }
+
+        self.play_opponents();
+        self.observation()
+    }
+
+    /// Takes action number `action` for the agent, then lets the bots play
+    /// until it's the agent's turn again or the game is over. Returns the
+    /// agent's observation, a reward of 1 if it won, -1 if someone else did
+    /// and 0 otherwise, and whether the game is over.
+    pub fn step(
+        &mut self,
+        action: usize,
+    ) -> Result<([f32; OBSERVATION_SIZE], f32, bool), RuleError> {
+        if self.done() {
+            return Err(RuleError::GameOver);
+        }
+        if !self.action_mask().get(action).copied().unwrap_or(false) {
+            return Err(RuleError::IllegalCard);
+        }
+
+        let action = self.action(action);
+        self.apply(&action)?;
+        self.turns += 1;
+        self.play_opponents();
+
+        let reward = match self.game.winner() {
+            Some(0) => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+
+        Ok((self.observation(), reward, self.done()))
+    }
+
+    /// Whether the game is over, won or called off.
+    pub fn done(&self) -> bool {
+        self.game.winner().is_some() || self.turns >= MAX_TURNS
+    }
+
+    /// The game being played.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Which actions the agent can take right now.
+    pub fn action_mask(&self) -> [bool; ACTIONS] {
+        let mut result = [false; ACTIONS];
+        if self.done() || self.game.current_turn != 0 {
+            return result;
+        }
+
+        for card in self.game.players[0].cards.iter() {
+            if card.is_wild() {
+                for color in CardColor::ALL {
+                    let mut colored = *card;
+                    colored.set_color(color);
+                    if self.game.can_use(colored) {
+                        result[action_index(card, color).unwrap()] = true;
+                    }
+                }
+            } else if self.game.can_use(*card) {
+                result[card_type(card)] = true;
+            }
+        }
+        result[DRAW] = true;
+
+        result
+    }
+
+    /// What the agent can see, as numbers.
+    pub fn observation(&self) -> [f32; OBSERVATION_SIZE] {
+        let mut result = [0.0; OBSERVATION_SIZE];
+
+        for card in self.game.players[0].cards.iter() {
+            result[card_type(card)] += 1.0;
+        }
+
+        let top = &self.game.last_card;
+        result[CARD_TYPES + card_type(top)] = 1.0;
+        if let Some(color) = color_index(top.color) {
+            result[CARD_TYPES * 2 + color] = 1.0;
+        }
+
+        let opponents = CARD_TYPES * 2 + 4;
+        for (index, player) in self.game.players.iter().skip(1).enumerate() {
+            result[opponents + index] = player.card_num() as f32;
+        }
+
+        result[OBSERVATION_SIZE - 2] = match self.game.direction() {
+            Direction::Right => 1.0,
+            Direction::Left => -1.0,
+        };
+        result[OBSERVATION_SIZE - 1] = self.game.plus as f32;
+
+        result
+    }
+
+    /// The move action number `action` stands for, which has been checked
+    /// to be legal.
+    fn action(&self, action: usize) -> Action {
+        let name = String::from("agent");
+        if action == DRAW {
+            return Action::Draw { name };
+        }
+
+        for card in self.game.players[0].cards.iter() {
+            if card.is_wild() {
+                for color in CardColor::ALL {
+                    if action_index(card, color) == Some(action) {
+                        return Action::UseCard {
+                            name,
+                            card_id: card.id,
+                            color: Some(color),
+                        };
+                    }
+                }
+            } else if card_type(card) == action {
+                return Action::UseCard {
+                    name,
+                    card_id: card.id,
+                    color: None,
+                };
+            }
+        }
+
+        Action::Draw { name }
+    }
+
+    /// Makes a move, keeping track of what it tells the bots.
+    fn apply(&mut self, action: &Action) -> Result<(), RuleError> {
+        let events = self.game.apply(action)?;
+        self.voids.update(&self.game, &events);
+        Ok(())
+    }
+
+    /// Lets the bots move until it's the agent's turn or the game is over.
+    fn play_opponents(&mut self) {
+        while !self.done() && self.game.current_turn != 0 {
+            let id = self.game.current_turn;
+            let moves = self.game.legal_moves(id);
+            let action =
+                self.bots[id as usize - 1].choose(&self.voids.observe(&self.game, id), &moves);
+
+            if self.apply(&action).is_err() {
+                let _ = self.apply(&moves[moves.len() - 1]);
+            }
+            self.turns += 1;
+        }
+    }
+}
@@ -23,37 +23,181 @@ SOFTWARE.
 */
 
 use crate::{
-    cards::{self, CardColor, CardValue},
+    cards::{self, Card, CardColor},
     player::{self, Player},
 };
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{fmt, fs};
 
-enum Direction {
+/// Which way play goes around the table. Games start going `Right`, which
+/// is clockwise.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Direction {
     Right,
     Left,
 }
 
 impl Direction {
-    pub fn flip(&mut self) -> Self {
-        match self {
+    /// Turns play around.
+    pub fn flip(&mut self) {
+        *self = match self {
             Direction::Left => Direction::Right,
             Direction::Right => Direction::Left,
+        };
+    }
+}
+
+/// Everything a client can do that changes the game. The server turns each
+/// request into one of these, which is also what replays are made of.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Action {
+    Join {
+        name: String,
+    },
+    /// Plays a card, picking its color first if `color` is given.
+    UseCard {
+        name: String,
+        card_id: u16,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<CardColor>,
+    },
+    /// Draws the penalty, or one card if there isn't one, and ends the turn.
+    Draw {
+        name: String,
+    },
+    CycleColorUp {
+        name: String,
+        card_id: u16,
+    },
+    CycleColorDown {
+        name: String,
+        card_id: u16,
+    },
+    SetColor {
+        name: String,
+        card_id: u16,
+        color: CardColor,
+    },
+}
+
+/// Why a move isn't allowed. The server answers a rejected play with the
+/// error's `code`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RuleError {
+    IllegalCard,
+    NotYourTurn,
+    MustDrawPenalty,
+    NoColorChosen,
+    UnknownPlayer,
+    UnknownCard,
+    GameOver,
+}
+
+impl RuleError {
+    /// The byte the server answers a rejected play with.
+    pub fn code(self) -> u8 {
+        match self {
+            RuleError::IllegalCard => 1,
+            RuleError::NotYourTurn => 2,
+            RuleError::MustDrawPenalty => 3,
+            RuleError::NoColorChosen => 4,
+            RuleError::UnknownPlayer => 5,
+            RuleError::UnknownCard => 6,
+            RuleError::GameOver => 7,
+        }
+    }
+
+    /// The error a server answer stands for, if it's one.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(RuleError::IllegalCard),
+            2 => Some(RuleError::NotYourTurn),
+            3 => Some(RuleError::MustDrawPenalty),
+            4 => Some(RuleError::NoColorChosen),
+            5 => Some(RuleError::UnknownPlayer),
+            6 => Some(RuleError::UnknownCard),
+            7 => Some(RuleError::GameOver),
+            _ => None,
         }
     }
 }
 
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RuleError::IllegalCard => "That card doesn't match the top card",
+            RuleError::NotYourTurn => "It's not your turn",
+            RuleError::MustDrawPenalty => "Stack a +2 or +4, or draw the penalty",
+            RuleError::NoColorChosen => "Pick a color for the wild first",
+            RuleError::UnknownPlayer => "You're not in this game",
+            RuleError::UnknownCard => "There's no such card",
+            RuleError::GameOver => "The game is over",
+        })
+    }
+}
+
+/// Something that happened in the game. [`Game::apply`] returns them in the
+/// order they happened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// `name` sat down and was dealt their hand.
+    Joined { name: String },
+    /// `name` drew `count` cards.
+    Drew { name: String, count: u8 },
+    /// `name` put `card` on the discard pile.
+    Played { name: String, card: cards::Card },
+    /// `name` picked the color of a wild card in their hand.
+    ColorChosen {
+        name: String,
+        card_id: u16,
+        color: CardColor,
+    },
+    /// A +2 or +4 made the penalty `plus` cards.
+    PenaltyRaised { plus: u8 },
+    /// Nobody has cards waiting to be drawn anymore.
+    PenaltyCleared,
+    /// Play now goes the other way.
+    Reversed { direction: Direction },
+    /// `name` misses their turn.
+    Skipped { name: String },
+    /// The deck ran out and the discard pile was shuffled into it.
+    DeckReshuffled { cards: usize },
+    /// It's `name`'s turn.
+    TurnPassed { name: String },
+    /// `name` played their last card.
+    Won { name: String },
+}
+
+/// A whole game: the table, the deck and the discard pile.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
+    /// Everyone at the table, in the order they joined.
     pub players: Vec<player::Player>,
     deck: cards::Deck,
+    /// The cards under the top card, oldest first.
+    #[serde(default)]
+    discard: Vec<cards::Card>,
     direction: Direction,
+    /// Index into `players` of whoever's turn it is.
     pub current_turn: u8,
+    /// The card on top of the discard pile.
     pub last_card: cards::Card,
+    /// How many cards the next player has to draw unless they stack.
     pub plus: u8,
+    #[serde(default)]
+    seed: u64,
+    /// How many times the discard pile has been shuffled back into the deck.
+    #[serde(default)]
+    reshuffles: u64,
 }
 
 impl Game {
-    pub fn new() -> Self {
-        let mut deck = cards::Deck::new();
-
+    /// Starts a game that deals from `deck` as-is, so the same deck always
+    /// produces the same game.
+    pub fn from_deck(mut deck: cards::Deck) -> Self {
         let mut starting_card = deck.take_card().unwrap();
 
         if starting_card.color == cards::CardColor::None {
@@ -63,43 +207,218 @@ impl Game {
         Game {
             players: Vec::<Player>::new(),
             deck,
+            discard: vec![],
             direction: Direction::Right,
             current_turn: 0,
             last_card: starting_card,
             plus: 0,
+            seed: 0,
+            reshuffles: 0,
+        }
+    }
+
+    /// Rebuilds a game from what one player can see of it, dealing the cards
+    /// they can't see from `hidden`: everyone else's hands in seat order,
+    /// then the deck. Bots use this to try out moves on guesses of the
+    /// hidden cards.
+    pub fn from_observation(observation: &Observation, hidden: Vec<cards::Card>) -> Self {
+        let mut hidden = hidden.into_iter();
+        let players = observation
+            .players
+            .iter()
+            .enumerate()
+            .map(|(seat, val)| {
+                let mut player = Player::new(val.name.clone());
+                player.cards = if seat == observation.seat as usize {
+                    observation.hand.clone()
+                } else {
+                    hidden.by_ref().take(val.cards).collect()
+                };
+                player
+            })
+            .collect();
+
+        Game {
+            players,
+            deck: cards::Deck::from_cards(hidden.collect()),
+            discard: observation.discard.clone(),
+            direction: observation.direction,
+            current_turn: observation.current_turn,
+            last_card: observation.top_card,
+            plus: observation.plus,
+            seed: 0,
+            reshuffles: 0,
         }
     }
 
+    /// Shuffles the discard pile back into the deck with `seed` whenever the
+    /// deck runs out, instead of the default seed of 0.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Reads a game saved with [`Game::save`].
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed reading {}: {}", path, e))?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid save {}: {}", path, e))
+    }
+
+    /// Writes the whole game to `path` as JSON.
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let data = serde_json::to_vec(self).unwrap();
+        crate::write_file(path, &data)
+    }
+
+    /// Whether `card` can go on the discard pile right now.
     pub fn can_use(&self, card: cards::Card) -> bool {
-        if self.plus != 0 {
-            return (card.value == CardValue::WildPlusFour || card.value == CardValue::PlusTwo)
-                && (self.last_card.value == card.value || self.last_card.color == card.color);
+        card.can_play_on(&self.last_card, self.plus)
+    }
+
+    /// Index into `players` of whoever played their last card, once someone has.
+    pub fn winner(&self) -> Option<u8> {
+        self.players
+            .iter()
+            .position(|player| player.won())
+            .map(|id| id as u8)
+    }
+
+    /// Whose turn it is, unless the game is over or `name` isn't playing.
+    fn check_turn(&self, name: &str) -> Result<u8, RuleError> {
+        let id = self.player_index(name).ok_or(RuleError::UnknownPlayer)?;
+
+        if self.winner().is_some() {
+            Err(RuleError::GameOver)
+        } else if self.current_turn != id {
+            Err(RuleError::NotYourTurn)
+        } else {
+            Ok(id)
         }
+    }
+
+    /// Whether `name` can play their card with `card_id` right now, and why
+    /// not if they can't.
+    pub fn check_play(&self, name: &str, card_id: u16) -> Result<(), RuleError> {
+        let id = self.player_index(name).ok_or(RuleError::UnknownPlayer)?;
+        let player = &self.players[id as usize];
+        let card = player
+            .card_index(card_id)
+            .map(|index| player.cards[index])
+            .ok_or(RuleError::UnknownCard)?;
 
-        if (card.value == cards::CardValue::Wild || card.value == cards::CardValue::WildPlusFour)
-            && card.color != CardColor::None
+        self.check_turn(name)?;
+
+        if self.can_use(card) {
+            Ok(())
+        } else if card.is_wild()
+            && card.color == CardColor::None
+            && (self.plus == 0 || card.value == cards::CardValue::WildPlusFour)
         {
-            return true;
+            // A +4 can still go on a penalty once it has a color.
+            Err(RuleError::NoColorChosen)
+        } else if self.plus != 0 {
+            Err(RuleError::MustDrawPenalty)
+        } else {
+            Err(RuleError::IllegalCard)
         }
+    }
 
-        if self.last_card.value == card.value || self.last_card.color == card.color {
-            return true;
+    /// Every play the player at index `id` could make from their hand, turn
+    /// aside: one per color for wilds, since the color decides whether they
+    /// can go on a +2 or +4.
+    fn plays(&self, id: u8) -> Vec<Action> {
+        let player = &self.players[id as usize];
+        let mut result = vec![];
+
+        for card in player.cards.iter() {
+            if card.is_wild() {
+                for color in CardColor::ALL {
+                    let mut colored = *card;
+                    colored.set_color(color);
+                    if self.can_use(colored) {
+                        result.push(Action::UseCard {
+                            name: player.name.clone(),
+                            card_id: card.id,
+                            color: Some(color),
+                        });
+                    }
+                }
+            } else if self.can_use(*card) {
+                result.push(Action::UseCard {
+                    name: player.name.clone(),
+                    card_id: card.id,
+                    color: None,
+                });
+            }
         }
 
-        false
+        result
     }
 
-    pub fn add_player(&mut self, player: player::Player) {
-        self.players.push(player);
+    /// Everything the player at index `id` is allowed to do right now: every
+    /// card they can play, with each color a wild can be played as, and
+    /// drawing, which always comes last. Empty when it isn't their turn or
+    /// the game is over.
+    pub fn legal_moves(&self, id: u8) -> Vec<Action> {
+        let player = match self.players.get(id as usize) {
+            Some(player) => player,
+            None => return vec![],
+        };
+        if self.check_turn(&player.name).is_err() {
+            return vec![];
+        }
 
-        let last_player = self.players.len() - 1;
-        let player = &mut self.players[last_player];
+        let mut result = self.plays(id);
+        result.push(Action::Draw {
+            name: player.name.clone(),
+        });
 
-        for _ in 0..7 {
-            self.deck.give_card(player);
+        result
+    }
+
+    /// The ids of the cards `name` holds that could go on the discard pile,
+    /// whether or not it's their turn, counting wilds that still need a
+    /// color picked.
+    pub fn playable_cards(&self, name: &str) -> Vec<u16> {
+        let id = match self.player_index(name) {
+            Some(id) => id,
+            None => return vec![],
+        };
+
+        let mut result: Vec<u16> = vec![];
+        for action in self.plays(id) {
+            if let Action::UseCard { card_id, .. } = action {
+                if !result.contains(&card_id) {
+                    result.push(card_id);
+                }
+            }
         }
+
+        result
+    }
+
+    /// Which way play is going.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The cards left to draw.
+    pub fn deck(&self) -> &cards::Deck {
+        &self.deck
+    }
+
+    /// The cards under the top card, oldest first.
+    pub fn discard(&self) -> &[cards::Card] {
+        &self.discard
+    }
+
+    /// How many cards are left to draw before the discard pile is shuffled
+    /// back in.
+    pub fn deck_size(&self) -> usize {
+        self.deck.len()
     }
 
+    /// Index into `players` of whoever plays after the current player.
     pub fn next_player(&self) -> u8 {
         let mut result = self.current_turn as i8;
 
@@ -117,42 +436,286 @@ impl Game {
         result as u8
     }
 
-    pub fn cycle_turn(&mut self) {
-        self.current_turn = self.next_player();
+    /// Index into `players` of the player called `name`.
+    pub fn player_index(&self, name: &str) -> Option<u8> {
+        self.players
+            .iter()
+            .position(|val| val.name == name)
+            .map(|id| id as u8)
     }
 
-    pub fn take_card(&mut self, id: u8) {
-        self.deck.give_card(&mut self.players[id as usize]);
-    }
+    /// Checks `action` against the rules and, if it's allowed, carries it out.
+    /// Nothing changes when it isn't.
+    pub fn apply(&mut self, action: &Action) -> Result<Vec<Event>, RuleError> {
+        let mut events = vec![];
 
-    pub fn play_card(&mut self, card: cards::Card) -> bool {
-        if !self.can_use(card) {
-            return false;
+        match action {
+            Action::Join { name } => {
+                // Joining again after a reconnect is fine, it just does nothing.
+                if self.player_index(name).is_none() {
+                    self.players.push(Player::new(name.clone()));
+                    events.push(Event::Joined { name: name.clone() });
+                    let id = (self.players.len() - 1) as u8;
+                    self.deal(id, 7, &mut events);
+                }
+            }
+            Action::Draw { name } => {
+                let id = self.check_turn(name)?;
+                let num = self.plus.max(1);
+                self.deal(id, num, &mut events);
+                if self.plus != 0 {
+                    self.plus = 0;
+                    events.push(Event::PenaltyCleared);
+                }
+                self.pass_turn(&mut events);
+            }
+            Action::UseCard {
+                name,
+                card_id,
+                color,
+            } => {
+                if let Some(color) = color {
+                    let card = self.card_mut(name, *card_id)?;
+                    if !card.is_wild() || *color == CardColor::None {
+                        return Err(RuleError::IllegalCard);
+                    }
+                    // Only keep the color if the play goes through.
+                    let previous = card.color;
+                    card.set_color(*color);
+                    if let Err(e) = self.check_play(name, *card_id) {
+                        self.card_mut(name, *card_id)?.color = previous;
+                        return Err(e);
+                    }
+                } else {
+                    self.check_play(name, *card_id)?;
+                }
+                self.play(*card_id, &mut events);
+            }
+            Action::CycleColorUp { name, card_id } => {
+                let card = self.card_mut(name, *card_id)?;
+                if card.is_wild() {
+                    card.cycle_colors_up();
+                    events.push(Event::ColorChosen {
+                        name: name.clone(),
+                        card_id: *card_id,
+                        color: card.color,
+                    });
+                }
+            }
+            Action::CycleColorDown { name, card_id } => {
+                let card = self.card_mut(name, *card_id)?;
+                if card.is_wild() {
+                    card.cycle_colors_down();
+                    events.push(Event::ColorChosen {
+                        name: name.clone(),
+                        card_id: *card_id,
+                        color: card.color,
+                    });
+                }
+            }
+            Action::SetColor {
+                name,
+                card_id,
+                color,
+            } => {
+                let card = self.card_mut(name, *card_id)?;
+                if !card.is_wild() || *color == CardColor::None {
+                    return Err(RuleError::IllegalCard);
+                }
+                card.set_color(*color);
+                events.push(Event::ColorChosen {
+                    name: name.clone(),
+                    card_id: *card_id,
+                    color: *color,
+                });
+            }
         }
 
+        Ok(events)
+    }
+
+    /// Plays the current player's card with `card_id`, which has already
+    /// been checked.
+    fn play(&mut self, card_id: u16, events: &mut Vec<Event>) {
+        let player = &mut self.players[self.current_turn as usize];
+        let index = player.card_index(card_id).unwrap();
+        let card = player.take_card(index);
+        let name = player.name.clone();
+        let won = player.won();
+
+        self.discard.push(self.last_card);
         self.last_card = card;
+        events.push(Event::Played {
+            name: name.clone(),
+            card,
+        });
 
         match card.value {
-            cards::CardValue::Num(_) => {}
+            cards::CardValue::Num(_) | cards::CardValue::Wild => {}
             cards::CardValue::PlusTwo => {
                 self.plus += 2;
+                events.push(Event::PenaltyRaised { plus: self.plus });
             }
             cards::CardValue::WildPlusFour => {
                 self.plus += 4;
+                events.push(Event::PenaltyRaised { plus: self.plus });
             }
             cards::CardValue::Reverse => {
                 self.direction.flip();
+                events.push(Event::Reversed {
+                    direction: self.direction,
+                });
                 if self.players.len() == 2 {
-                    self.cycle_turn(); // To make the reverse card work as skip when there are only 2 players
+                    self.skip(events); // To make the reverse card work as skip when there are only 2 players
                 }
             }
-            cards::CardValue::Skip => {
-                self.cycle_turn();
-            }
-            cards::CardValue::Wild => {}
+            cards::CardValue::Skip => self.skip(events),
         };
-        self.deck.return_card(card);
-        self.cycle_turn();
-        true
+
+        if won {
+            events.push(Event::Won { name });
+        } else {
+            self.pass_turn(events);
+        }
+    }
+
+    fn skip(&mut self, events: &mut Vec<Event>) {
+        self.current_turn = self.next_player();
+        events.push(Event::Skipped {
+            name: self.players[self.current_turn as usize].name.clone(),
+        });
+    }
+
+    fn pass_turn(&mut self, events: &mut Vec<Event>) {
+        self.current_turn = self.next_player();
+        events.push(Event::TurnPassed {
+            name: self.players[self.current_turn as usize].name.clone(),
+        });
+    }
+
+    /// Deals up to `num` cards to the player at index `id`, shuffling the
+    /// discard pile back in when the deck runs out.
+    fn deal(&mut self, id: u8, num: u8, events: &mut Vec<Event>) {
+        let mut count = 0;
+
+        for _ in 0..num {
+            if self.deck.len() == 0 {
+                self.reshuffle(events);
+            }
+            match self.deck.take_card() {
+                Some(card) => {
+                    self.players[id as usize].add_card(card);
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+
+        events.push(Event::Drew {
+            name: self.players[id as usize].name.clone(),
+            count,
+        });
+    }
+
+    fn reshuffle(&mut self, events: &mut Vec<Event>) {
+        if self.discard.is_empty() {
+            return;
+        }
+
+        let mut cards = std::mem::take(&mut self.discard);
+        for card in cards.iter_mut() {
+            if card.is_wild() {
+                card.color = CardColor::None;
+            }
+        }
+
+        self.reshuffles += 1;
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.reshuffles));
+        let mut deck = cards::Deck::from_cards(cards);
+        deck.shuffle_with(&mut rng);
+        events.push(Event::DeckReshuffled { cards: deck.len() });
+        self.deck = deck;
+    }
+
+    /// The card with `card_id` in `name`'s hand.
+    fn card_mut(&mut self, name: &str, card_id: u16) -> Result<&mut cards::Card, RuleError> {
+        let id = self.player_index(name).ok_or(RuleError::UnknownPlayer)?;
+        let player = &mut self.players[id as usize];
+        let index = player.card_index(card_id).ok_or(RuleError::UnknownCard)?;
+        Ok(&mut player.cards[index])
+    }
+}
+
+/// A color someone drew on instead of following. The cards they held then
+/// probably didn't match it, but the ones they drew since might.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Void {
+    pub color: CardColor,
+    /// How many cards they held before drawing.
+    pub hand_size: usize,
+}
+
+/// Someone else at the table, as far as the other players can tell.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Seat {
+    pub name: String,
+    pub cards: usize,
+    /// Colors they drew on since they last played, as far as whoever made
+    /// the observation kept track. At most one per color.
+    #[serde(default)]
+    pub voids: Vec<Void>,
+}
+
+/// What the player at one seat can see of the game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    /// The seat of the player this is for.
+    pub seat: u8,
+    pub hand: Vec<Card>,
+    pub top_card: Card,
+    /// The cards under the top card, oldest first.
+    #[serde(default)]
+    pub discard: Vec<Card>,
+    /// How many cards are waiting to be drawn.
+    pub plus: u8,
+    pub direction: Direction,
+    pub current_turn: u8,
+    /// Everyone at the table, by seat.
+    pub players: Vec<Seat>,
+    /// How many cards are left in the deck.
+    pub deck: usize,
+}
+
+impl Observation {
+    /// What the player at `id` can see of `game`, without any
+    /// [`Void`]s. [`bot::Voids`](crate::bot::Voids) fills those in.
+    pub fn new(game: &Game, id: u8) -> Self {
+        Observation {
+            seat: id,
+            hand: game.players[id as usize].cards.clone(),
+            top_card: game.last_card,
+            discard: game.discard().to_vec(),
+            plus: game.plus,
+            direction: game.direction(),
+            current_turn: game.current_turn,
+            players: game
+                .players
+                .iter()
+                .map(|player| Seat {
+                    name: player.name.clone(),
+                    cards: player.card_num(),
+                    voids: vec![],
+                })
+                .collect(),
+            deck: game.deck_size(),
+        }
+    }
+
+    /// The name of whoever played their last card, once someone has.
+    pub fn winner(&self) -> Option<&str> {
+        self.players
+            .iter()
+            .find(|player| player.cards == 0)
+            .map(|player| &player.name[..])
     }
 }
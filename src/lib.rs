@@ -0,0 +1,72 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! The rules engine and network protocol behind the Uno TUI.
+//!
+//! [`Game`](game::Game) holds a whole game and changes only through
+//! [`Game::apply`](game::Game::apply), so the same [`Action`](game::Action)s
+//! replay to the same game. [`server::start_server`] hosts a game over TCP
+//! and [`client::Client`] talks to one with the [`protocol::Request`]s.
+
+pub mod bot;
+pub mod cards;
+pub mod client;
+pub mod discovery;
+pub mod env;
+pub mod game;
+pub mod player;
+pub mod protocol;
+pub mod replay;
+pub mod server;
+pub mod simulate;
+pub mod stats;
+
+/// Adds `message` as a line to the log file at `path`, or writes it to
+/// stderr without one. The host's TUI owns stdout, so anything running
+/// behind it can't print there.
+pub(crate) fn log(path: Option<&str>, message: &str) {
+    use std::io::Write;
+
+    let file = path.and_then(|path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .ok()
+    });
+    match file {
+        Some(mut file) => {
+            let _ = writeln!(file, "{}", message);
+        }
+        None => eprintln!("{}", message),
+    }
+}
+
+/// Writes `data` to a temporary file next to `path` and renames it over
+/// `path`, so a crash part way through leaves the old file whole.
+pub(crate) fn write_file(path: &str, data: &[u8]) -> Result<(), String> {
+    let temp = format!("{}.tmp", path);
+    std::fs::write(&temp, data).map_err(|e| format!("Failed writing {}: {}", temp, e))?;
+    std::fs::rename(&temp, path).map_err(|e| format!("Failed writing {}: {}", path, e))
+}
@@ -22,18 +22,63 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-mod cards;
-mod client;
-mod game;
-mod player;
-mod server;
+mod config;
 mod ui;
 
-use std::thread;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    thread,
+    time::Duration,
+};
 
-use clap::{App, Arg};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use uno::{bot, client, game::Game, replay, server, simulate, stats};
+
+const DEFAULT_STATS: &str = "uno-stats.json";
+const DEFAULT_LOG: &str = "uno.log";
+
+fn number<T: FromStr>(matches: &ArgMatches, name: &str) -> Result<T, String> {
+    matches
+        .value_of(name)
+        .unwrap()
+        .parse()
+        .map_err(|_| format!("--{} needs a number", name))
+}
+
+/// Starts the bot programs given with `--external-bot`, named after the
+/// programs.
+fn external_bots(matches: &ArgMatches) -> Result<Vec<server::SeatedBot>, String> {
+    let timeout = Duration::from_millis(number(matches, "bot-timeout")?);
+    let log = matches.value_of("log").unwrap_or(DEFAULT_LOG);
+    let mut result: Vec<server::SeatedBot> = vec![];
+
+    for command in matches.values_of("external-bot").into_iter().flatten() {
+        let program = command.split_whitespace().next().unwrap_or("bot");
+        let mut name = Path::new(program).file_stem().map_or_else(
+            || String::from("bot"),
+            |stem| stem.to_string_lossy().into_owned(),
+        );
+        if result.iter().any(|(val, _)| *val == name) {
+            name = format!("{} {}", name, result.len() + 1);
+        }
+
+        let bot = bot::External::spawn(command, timeout)?.with_log(log);
+        result.push((name, Box::new(bot)));
+    }
+
+    Ok(result)
+}
 
 fn main() {
+    let bot_help = format!(
+        "Join the game as a bot instead of opening the TUI, playing one of: {}",
+        bot::STRATEGIES.join(", ")
+    );
+    let bots_help = format!(
+        "Comma-separated strategies, one per seat: {}",
+        bot::STRATEGIES.join(", ")
+    );
     let matches = App::new("Uno tui")
         .version("0.1.0")
         .author("P3qch")
@@ -59,21 +104,326 @@ fn main() {
                 .takes_value(true)
                 .help("The number milisecons to wait for an event"),
         )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .help("The config file to use instead of the one in your config directory"),
+        )
+        .arg(
+            Arg::with_name("theme")
+                .long("theme")
+                .takes_value(true)
+                .possible_values(&ui::ThemeName::NAMES)
+                .help("The card colors to use"),
+        )
+        .arg(
+            Arg::with_name("record")
+                .short("r")
+                .long("record")
+                .takes_value(true)
+                .conflicts_with("resume")
+                .help("Record the hosted game to a replay file"),
+        )
+        .arg(
+            Arg::with_name("save")
+                .long("save")
+                .takes_value(true)
+                .help("Save the hosted game to a file after every move"),
+        )
+        .arg(
+            Arg::with_name("resume")
+                .long("resume")
+                .takes_value(true)
+                .help("Host a game continued from a save file"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Shuffle the hosted game's deck with a fixed seed"),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .long("stats")
+                .takes_value(true)
+                .help("The file the hosted game keeps player statistics in"),
+        )
+        .arg(
+            Arg::with_name("room")
+                .long("room")
+                .takes_value(true)
+                .help("The name the hosted game is shown under on the local network"),
+        )
+        .arg(
+            Arg::with_name("bot")
+                .long("bot")
+                .takes_value(true)
+                .help(&bot_help),
+        )
+        .arg(
+            Arg::with_name("name")
+                .long("name")
+                .takes_value(true)
+                .requires("bot")
+                .help("The name the bot joins under"),
+        )
+        .arg(
+            Arg::with_name("external-bot")
+                .long("external-bot")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .requires("host")
+                .help("Seat a bot program in the hosted game, e.g. \"python3 bot.py\""),
+        )
+        .arg(
+            Arg::with_name("bot-timeout")
+                .long("bot-timeout")
+                .takes_value(true)
+                .default_value("5000")
+                .help("The number of milliseconds bot programs get to move"),
+        )
+        .arg(
+            Arg::with_name("log")
+                .long("log")
+                .takes_value(true)
+                .requires("host")
+                .help("The file the hosted game logs errors and bot mistakes to"),
+        )
+        .arg(
+            Arg::with_name("replay")
+                .long("replay")
+                .takes_value(true)
+                .help("Watch a recorded game"),
+        )
+        .subcommand(
+            SubCommand::with_name("export-stats")
+                .about("Prints the player statistics of a hosted server")
+                .arg(
+                    Arg::with_name("stats")
+                        .long("stats")
+                        .takes_value(true)
+                        .help("The statistics file to export"),
+                )
+                .arg(
+                    Arg::with_name("json")
+                        .long("json")
+                        .takes_value(false)
+                        .help("Print JSON instead of CSV"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("simulate")
+                .about("Plays bots against each other and reports how they did")
+                .arg(
+                    Arg::with_name("bots")
+                        .long("bots")
+                        .takes_value(true)
+                        .default_value("greedy,random")
+                        .help(&bots_help),
+                )
+                .arg(
+                    Arg::with_name("games")
+                        .short("n")
+                        .long("games")
+                        .takes_value(true)
+                        .default_value("1000")
+                        .help("How many games to play"),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .long("seed")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("The seed the first game is played with"),
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .short("j")
+                        .long("threads")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("How many games to play at once"),
+                )
+                .arg(
+                    Arg::with_name("max-turns")
+                        .long("max-turns")
+                        .takes_value(true)
+                        .default_value("5000")
+                        .help("Give up on games that take longer than this"),
+                )
+                .arg(
+                    Arg::with_name("csv")
+                        .long("csv")
+                        .takes_value(false)
+                        .help("Print CSV instead of a table"),
+                ),
+        )
         .get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("export-stats") {
+        let path = matches.value_of("stats").unwrap_or(DEFAULT_STATS);
+        match stats::Stats::load(path) {
+            Ok(stats) if matches.is_present("json") => {
+                println!("{}", serde_json::to_string_pretty(&stats).unwrap())
+            }
+            Ok(stats) => print!("{}", stats.to_csv()),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    if let Some(matches) = matches.subcommand_matches("simulate") {
+        let simulation = (|| {
+            Ok::<_, String>(simulate::Simulation {
+                bots: matches
+                    .value_of("bots")
+                    .unwrap()
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .collect(),
+                games: number(matches, "games")?,
+                seed: number(matches, "seed")?,
+                threads: number(matches, "threads")?,
+                max_turns: number(matches, "max-turns")?,
+            })
+        })();
+
+        match simulation.and_then(|simulation| simulation.run()) {
+            Ok(report) if matches.is_present("csv") => print!("{}", report.to_csv()),
+            Ok(report) => print!("{}", report.to_text()),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    let config_path = matches
+        .value_of("config")
+        .map(PathBuf::from)
+        .or_else(config::Config::default_path);
+    let config = match config_path.map(|path| config::Config::load(&path)) {
+        Some(Ok(config)) => config,
+        Some(Err(e)) => {
+            println!("{}", e);
+            return;
+        }
+        None => config::Config::default(),
+    };
+    let keymap = match config::Keymap::new(&config.keys) {
+        Ok(keymap) => keymap,
+        Err(e) => {
+            println!("Invalid key binding: {}", e);
+            return;
+        }
+    };
+
+    let ticks = match matches.value_of("ticks") {
+        Some(ticks) => ticks.parse().unwrap(),
+        None => config.ticks.unwrap_or(100),
+    };
+
+    let theme = ui::Theme::new(
+        matches
+            .value_of("theme")
+            .and_then(ui::ThemeName::parse)
+            .or(config.theme)
+            .unwrap_or(ui::ThemeName::Default),
+    );
+
+    if let Some(path) = matches.value_of("replay") {
+        match replay::Replay::load(path) {
+            Ok(replay) => ui::ReplayUI::new(replay, ticks, theme).replay_screen(),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    // The configured server is somewhere to join, not an address to host on.
+    let default_ip = match (matches.is_present("host"), config.server) {
+        (false, Some(server)) => server,
+        _ => String::from("127.0.0.1:8080"),
+    };
     let ip = matches
         .value_of("ip")
-        .unwrap_or("127.0.0.1:8080")
-        .to_string();
-    let ip2 = ip.clone();
+        .map(String::from)
+        .unwrap_or(default_ip);
     if matches.is_present("host") {
-        thread::spawn(|| server::start_server(ip2));
+        let ip2 = ip.clone();
+        let seed = match matches.value_of("seed").map(|_| number(&matches, "seed")) {
+            Some(Ok(seed)) => Some(seed),
+            Some(Err(e)) => {
+                println!("{}", e);
+                return;
+            }
+            None => None,
+        };
+        // A resumed game keeps saving to the file it came from.
+        let resume = match matches.value_of("resume").map(Game::load) {
+            Some(Ok(game)) => Some(game),
+            Some(Err(e)) => {
+                println!("{}", e);
+                return;
+            }
+            None => None,
+        };
+        let stats_path = matches.value_of("stats").unwrap_or(DEFAULT_STATS);
+        let initial_stats = match stats::Stats::load(stats_path) {
+            Ok(stats) => stats,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let options = server::ServerOptions {
+            record: matches.value_of("record").map(String::from),
+            seed,
+            save: matches
+                .value_of("save")
+                .or_else(|| matches.value_of("resume"))
+                .map(String::from),
+            resume,
+            stats: Some(stats_path.to_string()),
+            initial_stats,
+            room: Some(matches.value_of("room").unwrap_or("Uno").to_string()),
+            bots: match external_bots(&matches) {
+                Ok(bots) => bots,
+                Err(e) => {
+                    println!("{}", e);
+                    return;
+                }
+            },
+            log: Some(matches.value_of("log").unwrap_or(DEFAULT_LOG).to_string()),
+        };
+        thread::spawn(move || server::start_server(ip2, options));
     }
 
-    let mut ui = ui::GameUI::new(
-        ip,
-        matches.value_of("ticks").unwrap_or("100").parse().unwrap(),
-    );
+    if let Some(strategy) = matches.value_of("bot") {
+        let name = matches
+            .value_of("name")
+            .map(String::from)
+            .unwrap_or_else(|| format!("{} bot", strategy));
+        let mut bot = match bot::strategy(strategy, rand::random()) {
+            Some(bot) => bot,
+            None => {
+                println!("Unknown strategy \"{}\"", strategy);
+                return;
+            }
+        };
+
+        println!("Playing as {} on {}", name, ip);
+        let client = client::Client::new(ip);
+        let poll = Duration::from_millis(ticks);
+        let rejected = |e: &str| println!("{} couldn't make its move: {}", name, e);
+        match bot::network::play(&client, &name, bot.as_mut(), poll, rejected) {
+            Ok(winner) => println!("{} won", winner),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    let mut ui = ui::GameUI::new(ip, ticks, keymap, theme);
+    ui.name = config.name.unwrap_or_default();
     if ui.join_screen() {
         return;
     }
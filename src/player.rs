@@ -25,6 +25,7 @@ SOFTWARE.
 use crate::cards::Card;
 use serde::{Deserialize, Serialize};
 
+/// Someone at the table and the cards in their hand.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Player {
     pub cards: Vec<Card>,
@@ -33,6 +34,7 @@ pub struct Player {
 }
 
 impl Player {
+    /// A player with an empty hand.
     pub fn new(name: String) -> Self {
         Player {
             cards: vec![],
@@ -40,6 +42,7 @@ impl Player {
             frozen: false,
         }
     }
+    /// How many cards are in the hand.
     pub fn card_num(&self) -> usize {
         self.cards.len()
     }
@@ -48,9 +51,16 @@ impl Player {
         self.cards.push(card);
     }
 
+    /// Whether the hand is empty.
     pub fn won(&self) -> bool {
         self.cards.is_empty()
     }
+    /// Where the card with `id` is in the hand.
+    pub fn card_index(&self, id: u16) -> Option<usize> {
+        self.cards.iter().position(|card| card.id == id)
+    }
+
+    /// Removes the card at `index` from the hand.
     pub fn take_card(&mut self, index: usize) -> Card {
         self.cards.remove(index)
     }
@@ -0,0 +1,100 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! What clients send the server. Every request is one JSON object on its
+//! own connection, tagged with its `request_type`:
+//!
+//! ```json
+//! {"request_type": "use_card", "name": "alice", "card_id": 42}
+//! ```
+//!
+//! Requests that change the game get no answer, except `use_card` and
+//! `draw`, which answer with one byte: 0 if the move went through or a
+//! [`RuleError`](crate::game::RuleError) code. `save` also answers with a
+//! byte, 0 on success. The rest answer with JSON, a single byte
+//! (`get_plus`, `get_card_num`) or a bare player name (`current_turn`,
+//! `next_player`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::cards::CardColor;
+
+/// A message from a client to the server.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "request_type", rename_all = "snake_case")]
+pub enum Request {
+    /// Sits down at the table, or does nothing if `name` already has.
+    Join { name: String },
+    /// Saves the game to the server's save file. Answered with 0 once it's
+    /// saved, 1 if the server has no save file or 2 if writing it failed.
+    Save,
+    /// The server's [`Stats`](crate::stats::Stats).
+    GetStats,
+    /// Everyone at the table, as [`Player`](crate::player::Player)s.
+    GetPlayers,
+    /// Plays the card with `card_id` from `name`'s hand, picking its color
+    /// first if `color` is given.
+    UseCard {
+        name: String,
+        card_id: u16,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<CardColor>,
+    },
+    /// Draws the penalty, or one card if there isn't one, and ends the turn.
+    Draw { name: String },
+    /// What `name` can see of the game, as an
+    /// [`Observation`](crate::game::Observation), or `null` if they aren't
+    /// playing.
+    Observe { name: String },
+    /// Every [`Action`](crate::game::Action) `name` is allowed to make right
+    /// now.
+    LegalMoves { name: String },
+    /// The ids of the cards `name` could play on the discard pile, whether
+    /// or not it's their turn.
+    PlayableCards { name: String },
+    /// `name`'s hand, in the order the server keeps it.
+    GetCards { name: String },
+    /// How many cards are waiting to be drawn.
+    GetPlus,
+    /// How many cards `name` holds.
+    GetCardNum { name: String },
+    /// Whose turn it is.
+    CurrentTurn,
+    /// Who plays after the current player.
+    NextPlayer,
+    /// Which [`Direction`](crate::game::Direction) play is going.
+    GetDirection,
+    /// The card on top of the discard pile.
+    TopCard,
+    /// Picks the color a wild card is played as.
+    SetColor {
+        name: String,
+        card_id: u16,
+        color: CardColor,
+    },
+    /// Moves a wild card to the next color.
+    CycleColorUp { name: String, card_id: u16 },
+    /// Moves a wild card to the previous color.
+    CycleColorDown { name: String, card_id: u16 },
+}
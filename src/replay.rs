@@ -0,0 +1,136 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cards::{Card, Deck},
+    game::{Action, Game},
+};
+
+/// How often a live game's replay is written out. Each save rewrites the
+/// whole file, so saving after every action would get slower as the game
+/// goes on.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Step {
+    /// Milliseconds since the recording started.
+    pub time: u64,
+    pub action: Action,
+}
+
+/// A recorded game: the deck it was dealt from, who sat where and every
+/// action that was accepted, in order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    /// The seed the deck was shuffled with. Older replays don't have one.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub deck: Vec<Card>,
+    pub seating: Vec<String>,
+    pub steps: Vec<Step>,
+}
+
+impl Replay {
+    pub fn new(deck: &Deck, seed: Option<u64>) -> Self {
+        Replay {
+            seed,
+            deck: deck.cards().to_vec(),
+            seating: vec![],
+            steps: vec![],
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = fs::read(path).map_err(|e| format!("Failed reading {}: {}", path, e))?;
+        serde_json::from_slice(&data).map_err(|e| format!("Invalid replay {}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let data = serde_json::to_vec(self).unwrap();
+        crate::write_file(path, &data)
+    }
+
+    /// Rebuilds the game as it was after the first `steps` actions.
+    pub fn game_at(&self, steps: usize) -> Game {
+        let mut game = Game::from_deck(Deck::from_cards(self.deck.clone()))
+            .with_seed(self.seed.unwrap_or_default());
+
+        for step in self.steps.iter().take(steps) {
+            let _ = game.apply(&step.action);
+        }
+
+        game
+    }
+}
+
+/// Writes every accepted action of a live game to a replay file.
+pub struct Recorder {
+    path: String,
+    replay: Replay,
+    start: Instant,
+    saved: Instant,
+}
+
+impl Recorder {
+    pub fn new(path: String, deck: &Deck, seed: Option<u64>) -> Self {
+        Recorder {
+            path,
+            replay: Replay::new(deck, seed),
+            start: Instant::now(),
+            saved: Instant::now(),
+        }
+    }
+
+    /// Adds `action` to the replay. The file is only written every
+    /// [`SAVE_INTERVAL`], so call [`Recorder::save`] once the game is over.
+    pub fn record(&mut self, action: &Action) -> Result<(), String> {
+        if let Action::Join { name } = action {
+            self.replay.seating.push(name.clone());
+        }
+
+        self.replay.steps.push(Step {
+            time: self.start.elapsed().as_millis() as u64,
+            action: action.clone(),
+        });
+
+        if self.saved.elapsed() >= SAVE_INTERVAL {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes everything recorded so far to the replay file.
+    pub fn save(&mut self) -> Result<(), String> {
+        self.saved = Instant::now();
+        self.replay.save(&self.path)
+    }
+}
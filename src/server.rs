@@ -22,15 +22,187 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use serde_json::Value;
 use std::{
     io::{Read, Write},
     sync::{Arc, Mutex},
+    thread,
+    time::Duration,
 };
 
-use crate::player::Player;
+use crate::{
+    bot::{Bot, Voids},
+    cards::{CardValue, Deck},
+    discovery,
+    game::{Action, Event, Game, RuleError},
+    protocol::Request,
+    replay::Recorder,
+    stats::Stats,
+};
+
+/// How often the server checks whether it's one of its bots' turn.
+const BOT_POLL: Duration = Duration::from_millis(100);
+
+/// A bot the server plays for itself, and the name it sits down under.
+pub type SeatedBot = (String, Box<dyn Bot>);
+
+/// How the hosted game is set up.
+#[derive(Default)]
+pub struct ServerOptions {
+    /// Replay file to record the game to.
+    pub record: Option<String>,
+    pub seed: Option<u64>,
+    /// File the game is saved to after every move.
+    pub save: Option<String>,
+    /// A saved game to continue instead of dealing a new one.
+    pub resume: Option<Game>,
+    /// File the players' statistics are kept in.
+    pub stats: Option<String>,
+    /// The statistics so far, read from the `stats` file.
+    pub initial_stats: Stats,
+    /// Name to announce the game under on the local network. Games bound
+    /// to a loopback address aren't announced.
+    pub room: Option<String>,
+    /// Bots the server seats and plays for itself, by name.
+    pub bots: Vec<SeatedBot>,
+    /// File errors and the bots' rejected moves are logged to, since the
+    /// host's screen is taken up by the game.
+    pub log: Option<String>,
+}
+
+struct State {
+    game: Mutex<Game>,
+    recorder: Mutex<Option<Recorder>>,
+    save: Option<String>,
+    stats: Mutex<Stats>,
+    stats_path: Option<String>,
+    log: Option<String>,
+    /// What the moves so far say about the players' hands, for bots.
+    voids: Mutex<Voids>,
+}
+
+/// Writes the game to the save file, if there is one, and answers with
+/// the byte a save request gets: 0 once saved, 1 without a save file or 2
+/// if writing it failed.
+fn save(state: &State, game: &Game) -> u8 {
+    match &state.save {
+        Some(path) => match game.save(path) {
+            Ok(()) => 0,
+            Err(e) => {
+                crate::log(state.log.as_deref(), &e);
+                2
+            }
+        },
+        None => 1,
+    }
+}
+
+/// Counts a +4 and, when `name` has just emptied their hand, the finished game.
+fn update_stats(state: &State, game: &Game, name: &str, value: CardValue) {
+    let mut stats = state.stats.lock().unwrap();
+    let mut changed = false;
+
+    if value == CardValue::WildPlusFour {
+        stats.record_plus_four(name);
+        changed = true;
+    }
+    if let Some(id) = game.player_index(name) {
+        if game.players[id as usize].won() {
+            stats.record_game(&game.players);
+            changed = true;
+        }
+    }
+
+    if !changed {
+        return;
+    }
+    if let Some(path) = &state.stats_path {
+        if let Err(e) = stats.save(path) {
+            crate::log(state.log.as_deref(), &e);
+        }
+    }
+}
+
+/// Applies `action` to the game and, if it changed anything, records and
+/// saves it.
+fn apply(state: &State, game: &mut Game, action: Action) -> Result<(), RuleError> {
+    let events = game.apply(&action)?;
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    state.voids.lock().unwrap().update(game, &events);
+    if let Some(recorder) = state.recorder.lock().unwrap().as_mut() {
+        let mut result = recorder.record(&action);
+        if game.winner().is_some() {
+            result = result.and_then(|_| recorder.save());
+        }
+        if let Err(e) = result {
+            crate::log(state.log.as_deref(), &e);
+        }
+    }
+    for event in events.iter() {
+        if let Event::Played { name, card } = event {
+            update_stats(state, game, name, card.value);
+        }
+    }
+    save(state, game);
+
+    Ok(())
+}
+
+/// The byte a move is answered with: 0 if it went through, or why not.
+fn response(result: Result<(), RuleError>) -> u8 {
+    match result {
+        Ok(()) => 0,
+        Err(e) => e.code(),
+    }
+}
+
+/// Plays the turns of the server's own bots for as long as the server runs.
+fn play_bots(state: Arc<State>, mut bots: Vec<(u8, Box<dyn Bot>)>) {
+    let mut finished = false;
+
+    loop {
+        thread::sleep(BOT_POLL);
+
+        let (game, voids) = {
+            let game_lock = state.game.lock().unwrap();
+            let bot_turn = bots.iter().any(|(seat, _)| *seat == game_lock.current_turn);
+
+            // Bots wait for someone to play against.
+            if game_lock.players.len() < 2 || (!bot_turn && game_lock.winner().is_none()) {
+                continue;
+            }
+            (game_lock.clone(), state.voids.lock().unwrap().clone())
+        };
+
+        if game.winner().is_some() {
+            if !finished {
+                for (seat, bot) in bots.iter_mut() {
+                    bot.game_over(&voids.observe(&game, *seat));
+                }
+                finished = true;
+            }
+            continue;
+        }
+
+        let id = game.current_turn;
+        let bot = &mut bots.iter_mut().find(|(seat, _)| *seat == id).unwrap().1;
+        let moves = game.legal_moves(id);
+        let action = bot.choose(&voids.observe(&game, id), &moves);
 
-fn handle_connection(mut stream: std::net::TcpStream, game: Arc<Mutex<crate::game::Game>>) {
+        let mut game_lock = state.game.lock().unwrap();
+        // The game can't have moved on without the bot, but a player might
+        // have joined or picked a color while it was thinking.
+        if game_lock.current_turn == id && game_lock.legal_moves(id).contains(&action) {
+            if let Err(e) = apply(&state, &mut game_lock, action) {
+                crate::log(state.log.as_deref(), &format!("Bot move rejected: {}", e));
+            }
+        }
+    }
+}
+
+fn handle_connection(mut stream: std::net::TcpStream, state: Arc<State>) {
     let mut buf = [0u8; 1024];
 
     while match stream.read(&mut buf) {
@@ -40,173 +212,161 @@ fn handle_connection(mut stream: std::net::TcpStream, game: Arc<Mutex<crate::gam
                 return;
             }
 
-            let json: Value = serde_json::from_slice(&buf[0..(size)]).unwrap();
-            let mut game_lock = game.lock().unwrap();
-
-            match json["request_type"].as_str().unwrap() {
-                "join" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    if (*game_lock)
-                        .players
-                        .iter()
-                        .filter(|val| val.name == name)
-                        .count()
-                        == 0
-                    {
-                        let p = Player::new(name);
-
-                        (*game_lock).add_player(p);
-                    }
-                }
-                "get_players" => {
-                    let players_json = serde_json::json!((*game_lock).players).to_string();
+            let request: Request = match serde_json::from_slice(&buf[0..(size)]) {
+                Ok(request) => request,
+                Err(e) => {
+                    crate::log(state.log.as_deref(), &format!("Invalid request: {}", e));
+                    return;
+                }
+            };
+            let mut game_lock = state.game.lock().unwrap();
+
+            match request {
+                Request::Join { name } => {
+                    let _ = apply(&state, &mut game_lock, Action::Join { name });
+                }
+                Request::Save => {
+                    stream.write_all(&[save(&state, &game_lock)]).unwrap();
+                }
+                Request::GetStats => {
+                    let stats_json = serde_json::json!(*state.stats.lock().unwrap()).to_string();
+                    stream.write_all(stats_json.as_bytes()).unwrap();
+                }
+                Request::GetPlayers => {
+                    let players_json = serde_json::json!(game_lock.players).to_string();
                     stream.write_all(players_json.as_bytes()).unwrap();
                 }
-                "reset_plus" => {
-                    (*game_lock).plus = 0;
-                }
-                "take_cards" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let num = json["num"].as_i64().unwrap();
-                    let id = (*game_lock).players.iter().position(|val| val.name == name);
-
-                    if let Some(id) = id {
-                        if (*game_lock).current_turn == id as u8 {
-                            for _ in 0..num {
-                                (*game_lock).take_card(id as u8);
-                            }
-                            (*game_lock).cycle_turn();
-                        }
-                    }
-                }
-                "use_card" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let card_index = json["card_index"].as_i64().unwrap() as usize;
-                    let player_index = (*game_lock)
-                        .players
-                        .iter()
-                        .position(|val| val.name == name)
-                        .unwrap();
-
-                    let his_turn = (*game_lock).current_turn == player_index as u8;
-                    if his_turn {
-                        if (*game_lock)
-                            .can_use((*game_lock).players[player_index].cards[card_index])
-                        {
-                            let card = (*game_lock).players[player_index].take_card(card_index);
-                            let result = (*game_lock).play_card(card);
-                            stream
-                                .write_all(if result { &[0u8] } else { &[1u8] })
-                                .unwrap();
-                        } else {
-                            stream.write_all(&[1u8]).unwrap();
-                        }
-                    } else {
-                        stream.write_all(&[1u8]).unwrap();
-                    }
-                }
-                "get_cards" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let player_index = (*game_lock)
-                        .players
-                        .iter()
-                        .position(|val| val.name == name)
-                        .unwrap();
-
-                    let cards = &(*game_lock).players[player_index].cards;
+                Request::UseCard {
+                    name,
+                    card_id,
+                    color,
+                } => {
+                    let result = apply(
+                        &state,
+                        &mut game_lock,
+                        Action::UseCard {
+                            name,
+                            card_id,
+                            color,
+                        },
+                    );
+                    stream.write_all(&[response(result)]).unwrap();
+                }
+                Request::Draw { name } => {
+                    let result = apply(&state, &mut game_lock, Action::Draw { name });
+                    stream.write_all(&[response(result)]).unwrap();
+                }
+                Request::Observe { name } => {
+                    let observation = game_lock
+                        .player_index(&name)
+                        .map(|id| state.voids.lock().unwrap().observe(&game_lock, id));
+                    let json = serde_json::to_string(&observation).unwrap();
+                    stream.write_all(json.as_bytes()).unwrap();
+                }
+                Request::LegalMoves { name } => {
+                    let actions = match game_lock.player_index(&name) {
+                        Some(id) => game_lock.legal_moves(id),
+                        None => vec![],
+                    };
+                    let json = serde_json::to_string(&actions).unwrap();
+                    stream.write_all(json.as_bytes()).unwrap();
+                }
+                Request::PlayableCards { name } => {
+                    let json = serde_json::to_string(&game_lock.playable_cards(&name)).unwrap();
+                    stream.write_all(json.as_bytes()).unwrap();
+                }
+                Request::GetCards { name } => {
+                    // Players that haven't joined (yet) have no cards.
+                    let cards = match game_lock.player_index(&name) {
+                        Some(id) => game_lock.players[id as usize].cards.clone(),
+                        None => vec![],
+                    };
 
                     let json = serde_json::json!(cards).to_string();
 
                     stream.write_all(json.as_bytes()).unwrap();
                 }
-                "get_plus" => {
-                    let response = [(*game_lock).plus];
+                Request::GetPlus => {
+                    let response = [game_lock.plus];
                     stream.write_all(&response).unwrap();
                 }
-                "get_card_num" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let player_index = (*game_lock)
-                        .players
-                        .iter()
-                        .position(|val| val.name == name)
-                        .unwrap();
-
-                    let cards = (*game_lock).players[player_index].card_num();
+                Request::GetCardNum { name } => {
+                    let cards = match game_lock.player_index(&name) {
+                        Some(id) => game_lock.players[id as usize].card_num(),
+                        None => 0,
+                    };
 
                     let buf = [cards as u8];
 
                     stream.write_all(&buf).unwrap();
                 }
-                "current_turn" => {
-                    let player_index = (*game_lock).current_turn;
-                    stream
-                        .write_all((*game_lock).players[player_index as usize].name.as_bytes())
-                        .unwrap();
+                Request::CurrentTurn => {
+                    let player_index = game_lock.current_turn as usize;
+                    let name = match game_lock.players.get(player_index) {
+                        Some(player) => &player.name[..],
+                        None => "",
+                    };
+                    stream.write_all(name.as_bytes()).unwrap();
+                }
+                Request::NextPlayer => {
+                    // Working out who's next needs someone at the table.
+                    let name = if game_lock.players.is_empty() {
+                        ""
+                    } else {
+                        &game_lock.players[game_lock.next_player() as usize].name[..]
+                    };
+                    stream.write_all(name.as_bytes()).unwrap();
                 }
-                "top_card" => {
-                    let json: String = serde_json::json!(&(*game_lock).last_card).to_string();
+                Request::GetDirection => {
+                    let json = serde_json::to_string(&game_lock.direction()).unwrap();
 
                     stream.write_all(json.as_bytes()).unwrap();
                 }
-                "cycle_color_up" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let card_index = json["card_index"].as_i64().unwrap() as usize;
-                    let player_index = (*game_lock)
-                        .players
-                        .iter()
-                        .position(|val| val.name == name)
-                        .unwrap();
-
-                    (*game_lock).players[player_index].cards[card_index].cycle_colors_up();
-                }
-                "cycle_color_down" => {
-                    let name = json["name"]
-                        .as_str()
-                        .unwrap()
-                        .trim_matches('\"')
-                        .to_string();
-                    let card_index = json["card_index"].as_i64().unwrap() as usize;
-                    let player_index = (*game_lock)
-                        .players
-                        .iter()
-                        .position(|val| val.name == name)
-                        .unwrap();
-
-                    (*game_lock).players[player_index].cards[card_index].cycle_colors_down();
-                }
-                _ => (),
+                Request::TopCard => {
+                    let json: String = serde_json::json!(&game_lock.last_card).to_string();
+
+                    stream.write_all(json.as_bytes()).unwrap();
+                }
+                Request::SetColor {
+                    name,
+                    card_id,
+                    color,
+                } => {
+                    let _ = apply(
+                        &state,
+                        &mut game_lock,
+                        Action::SetColor {
+                            name,
+                            card_id,
+                            color,
+                        },
+                    );
+                }
+                Request::CycleColorUp { name, card_id } => {
+                    let _ = apply(
+                        &state,
+                        &mut game_lock,
+                        Action::CycleColorUp { name, card_id },
+                    );
+                }
+                Request::CycleColorDown { name, card_id } => {
+                    let _ = apply(
+                        &state,
+                        &mut game_lock,
+                        Action::CycleColorDown { name, card_id },
+                    );
+                }
             };
 
             true
         }
         Err(_) => {
-            println!(
-                "An error occurred, terminating connection with {}",
-                stream.peer_addr().unwrap()
+            crate::log(
+                state.log.as_deref(),
+                &format!(
+                    "An error occurred, terminating connection with {}",
+                    stream.peer_addr().unwrap()
+                ),
             );
             stream.shutdown(std::net::Shutdown::Both).unwrap();
             false
@@ -214,22 +374,73 @@ fn handle_connection(mut stream: std::net::TcpStream, game: Arc<Mutex<crate::gam
     } {}
 }
 
-pub fn start_server(ip: String) {
-    let listener =
-        std::net::TcpListener::bind(&ip[..]).expect(&format!("Failed listening on {}", &ip)[..]);
+pub fn start_server(ip: String, options: ServerOptions) {
+    let listener = std::net::TcpListener::bind(&ip[..])
+        .unwrap_or_else(|_| panic!("Failed listening on {}", &ip));
+
+    // Games without a seed get a random one, so their replays still
+    // reshuffle the same way.
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let (game, recorder) = match options.resume {
+        Some(game) => (game, None),
+        None => {
+            let deck = Deck::with_seed(seed);
+            let recorder = options
+                .record
+                .map(|path| Recorder::new(path, &deck, Some(seed)));
 
-    let game = Arc::new(Mutex::new(crate::game::Game::new()));
+            (Game::from_deck(deck).with_seed(seed), recorder)
+        }
+    };
+
+    let state = Arc::new(State {
+        game: Mutex::new(game),
+        recorder: Mutex::new(recorder),
+        save: options.save,
+        stats: Mutex::new(options.initial_stats),
+        voids: Mutex::new(Voids::default()),
+        stats_path: options.stats,
+        log: options.log,
+    });
+
+    if !options.bots.is_empty() {
+        let mut bots = vec![];
+        let mut game_lock = state.game.lock().unwrap();
+        for (name, bot) in options.bots {
+            let _ = apply(&state, &mut game_lock, Action::Join { name: name.clone() });
+            if let Some(seat) = game_lock.player_index(&name) {
+                bots.push((seat, bot));
+            }
+        }
+        drop(game_lock);
+
+        let state_clone = state.clone();
+        thread::spawn(move || play_bots(state_clone, bots));
+    }
+
+    // Nobody else on the network could join a game bound to loopback.
+    let address = listener.local_addr().unwrap();
+    if let Some(room) = options.room.filter(|_| !address.ip().is_loopback()) {
+        let port = address.port();
+        let state_clone = state.clone();
+        let announced = discovery::announce(room, port, move || {
+            state_clone.game.lock().unwrap().players.len()
+        });
+        if let Err(e) = announced {
+            crate::log(state.log.as_deref(), &e);
+        }
+    }
 
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                let game_clone = game.clone();
-                std::thread::spawn(move || {
-                    handle_connection(stream, game_clone);
+                let state_clone = state.clone();
+                thread::spawn(move || {
+                    handle_connection(stream, state_clone);
                 });
             }
             Err(_) => {
-                println!("Couln't handle connection");
+                crate::log(state.log.as_deref(), "Couldn't handle connection");
             }
         }
     }
@@ -0,0 +1,314 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Bot-vs-bot games played in-process, many at a time, to compare
+//! strategies.
+
+use std::{collections::BTreeMap, thread};
+
+use crate::{
+    bot::{self, Bot, Voids},
+    cards::{CardValue, Deck},
+    game::{Action, Event, Game},
+};
+
+/// How a batch of games is played.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    /// The strategy of every bot at the table. Seats rotate from game to
+    /// game, so nobody always goes first.
+    pub bots: Vec<String>,
+    pub games: u32,
+    /// Game `n` is dealt and played with `seed + n`.
+    pub seed: u64,
+    pub threads: usize,
+    /// Games still going after this many turns are given up on.
+    pub max_turns: u32,
+}
+
+/// What came out of a [`Simulation`].
+#[derive(Clone, Debug, Default)]
+pub struct Report {
+    pub bots: Vec<String>,
+    pub games: u32,
+    /// Games that hit the turn limit without a winner.
+    pub unfinished: u32,
+    /// Games won by each bot, in the order they were given.
+    pub wins: Vec<u32>,
+    /// Turns taken in the finished games, summed.
+    pub turns: u64,
+    /// How many cards the player to move held, counted every turn.
+    pub hand_sizes: BTreeMap<usize, u64>,
+    /// How many times each card was played.
+    pub cards_played: BTreeMap<CardValue, u64>,
+}
+
+fn card_name(value: CardValue) -> String {
+    match value {
+        CardValue::Num(n) => n.to_string(),
+        CardValue::Reverse => String::from("Reverse"),
+        CardValue::PlusTwo => String::from("+2"),
+        CardValue::Skip => String::from("Skip"),
+        CardValue::Wild => String::from("Wild"),
+        CardValue::WildPlusFour => String::from("+4"),
+    }
+}
+
+impl Simulation {
+    /// Plays every game and adds up the results.
+    pub fn run(&self) -> Result<Report, String> {
+        if self.bots.len() < 2 {
+            return Err(String::from("A game needs at least two bots"));
+        }
+        if let Some(name) = self
+            .bots
+            .iter()
+            .find(|name| bot::strategy(name, 0).is_none())
+        {
+            return Err(format!(
+                "Unknown strategy \"{}\", expected one of {}",
+                name,
+                bot::STRATEGIES.join(", ")
+            ));
+        }
+
+        // Games are split up by number, so the results don't depend on how
+        // many threads play them.
+        let threads = self.threads.max(1);
+        let reports = thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread| {
+                    scope.spawn(move || {
+                        let mut report = self.empty_report();
+                        let mut game = thread as u32;
+                        while game < self.games {
+                            self.play(game, &mut report);
+                            game += threads as u32;
+                        }
+                        report
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let mut result = self.empty_report();
+        for report in reports {
+            result.merge(report);
+        }
+
+        Ok(result)
+    }
+
+    fn empty_report(&self) -> Report {
+        Report {
+            bots: self.bots.clone(),
+            wins: vec![0; self.bots.len()],
+            ..Report::default()
+        }
+    }
+
+    /// Plays game number `number` into `report`.
+    fn play(&self, number: u32, report: &mut Report) {
+        let seed = self.seed.wrapping_add(number as u64);
+        let count = self.bots.len();
+        let mut game = Game::from_deck(Deck::with_seed(seed)).with_seed(seed);
+
+        // `seats[n]` is the bot sitting in seat `n`.
+        let seats: Vec<usize> = (0..count)
+            .map(|seat| (seat + number as usize) % count)
+            .collect();
+        let mut bots: Vec<Box<dyn Bot>> = seats
+            .iter()
+            .map(|index| bot::strategy(&self.bots[*index], seed ^ *index as u64).unwrap())
+            .collect();
+        let mut voids = Voids::default();
+        for index in seats.iter() {
+            if let Ok(events) = game.apply(&Action::Join {
+                name: format!("{} {}", index + 1, self.bots[*index]),
+            }) {
+                voids.update(&game, &events);
+            }
+        }
+
+        report.games += 1;
+
+        for turn in 0..self.max_turns {
+            if let Some(winner) = game.winner() {
+                report.wins[seats[winner as usize]] += 1;
+                report.turns += turn as u64;
+                return;
+            }
+
+            let id = game.current_turn;
+            *report
+                .hand_sizes
+                .entry(game.players[id as usize].card_num())
+                .or_default() += 1;
+
+            let moves = game.legal_moves(id);
+            let action = bots[id as usize].choose(&voids.observe(&game, id), &moves);
+            // A bot that picks something it can't do draws instead.
+            let events = match game.apply(&action) {
+                Ok(events) => events,
+                Err(_) => game.apply(&moves[moves.len() - 1]).unwrap(),
+            };
+            voids.update(&game, &events);
+
+            for event in events {
+                if let Event::Played { card, .. } = event {
+                    *report.cards_played.entry(card.value).or_default() += 1;
+                }
+            }
+        }
+
+        match game.winner() {
+            Some(winner) => {
+                report.wins[seats[winner as usize]] += 1;
+                report.turns += self.max_turns as u64;
+            }
+            None => report.unfinished += 1,
+        }
+    }
+}
+
+impl Report {
+    fn merge(&mut self, other: Report) {
+        self.games += other.games;
+        self.unfinished += other.unfinished;
+        self.turns += other.turns;
+        for (wins, other) in self.wins.iter_mut().zip(other.wins) {
+            *wins += other;
+        }
+        for (size, count) in other.hand_sizes {
+            *self.hand_sizes.entry(size).or_default() += count;
+        }
+        for (value, count) in other.cards_played {
+            *self.cards_played.entry(value).or_default() += count;
+        }
+    }
+
+    /// The share of all games the bot at `index` won.
+    pub fn win_rate(&self, index: usize) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.wins[index] as f64 / self.games as f64
+        }
+    }
+
+    /// Turns per finished game.
+    pub fn average_length(&self) -> f64 {
+        match self.games - self.unfinished {
+            0 => 0.0,
+            finished => self.turns as f64 / finished as f64,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let hand_total: u64 = self.hand_sizes.values().sum();
+        let cards_total: u64 = self.cards_played.values().sum();
+        let share = |count: u64, total: u64| match total {
+            0 => 0.0,
+            total => count as f64 * 100.0 / total as f64,
+        };
+
+        let mut result = format!(
+            "{} games, {} unfinished, {:.1} turns on average\n\n",
+            self.games,
+            self.unfinished,
+            self.average_length()
+        );
+
+        result.push_str("Bot              Wins  Win rate\n");
+        for (index, name) in self.bots.iter().enumerate() {
+            result.push_str(&format!(
+                "{:<15} {:>5}  {:>7.1}%\n",
+                format!("{} {}", index + 1, name),
+                self.wins[index],
+                self.win_rate(index) * 100.0
+            ));
+        }
+
+        result.push_str("\nHand size       Turns     Share\n");
+        for (size, count) in self.hand_sizes.iter() {
+            result.push_str(&format!(
+                "{:<9} {:>11}  {:>7.1}%\n",
+                size,
+                count,
+                share(*count, hand_total)
+            ));
+        }
+
+        result.push_str("\nCard           Played     Share\n");
+        for (value, count) in self.cards_played.iter() {
+            result.push_str(&format!(
+                "{:<9} {:>11}  {:>7.1}%\n",
+                card_name(*value),
+                count,
+                share(*count, cards_total)
+            ));
+        }
+
+        result
+    }
+
+    /// One `section,name,value` row per number in the report.
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from("section,name,value\n");
+
+        result.push_str(&format!("games,played,{}\n", self.games));
+        result.push_str(&format!("games,unfinished,{}\n", self.unfinished));
+        result.push_str(&format!(
+            "games,average_turns,{:.2}\n",
+            self.average_length()
+        ));
+        for (index, name) in self.bots.iter().enumerate() {
+            result.push_str(&format!(
+                "wins,{} {},{}\n",
+                index + 1,
+                name,
+                self.wins[index]
+            ));
+            result.push_str(&format!(
+                "win_rate,{} {},{:.4}\n",
+                index + 1,
+                name,
+                self.win_rate(index)
+            ));
+        }
+        for (size, count) in self.hand_sizes.iter() {
+            result.push_str(&format!("hand_size,{},{}\n", size, count));
+        }
+        for (value, count) in self.cards_played.iter() {
+            result.push_str(&format!("card_played,{},{}\n", card_name(*value), count));
+        }
+
+        result
+    }
+}
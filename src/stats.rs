@@ -0,0 +1,141 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{collections::BTreeMap, fs, io::ErrorKind};
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub games_won: u32,
+    pub points: u32,
+    /// Cards left in hand at the end of every game, summed.
+    pub cards_left: u32,
+    pub plus_fours_played: u32,
+}
+
+impl PlayerStats {
+    pub fn average_cards_left(&self) -> f64 {
+        if self.games_played == 0 {
+            0.0
+        } else {
+            self.cards_left as f64 / self.games_played as f64
+        }
+    }
+}
+
+/// Lifetime statistics of every player that finished a game on this server,
+/// by name.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub players: BTreeMap<String, PlayerStats>,
+}
+
+impl Stats {
+    /// Loads the stats file, starting empty if there isn't one yet.
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read(path) {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(|e| format!("Invalid stats {}: {}", path, e))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Stats::default()),
+            Err(e) => Err(format!("Failed reading {}: {}", path, e)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let data = serde_json::to_vec_pretty(self).unwrap();
+        crate::write_file(path, &data)
+    }
+
+    pub fn record_plus_four(&mut self, name: &str) {
+        self.players
+            .entry(name.to_string())
+            .or_default()
+            .plus_fours_played += 1;
+    }
+
+    /// Records a finished game. The winner scores the points of every card
+    /// left in the other players' hands.
+    pub fn record_game(&mut self, players: &[Player]) {
+        let points: u32 = players
+            .iter()
+            .flat_map(|player| player.cards.iter())
+            .map(|card| card.points())
+            .sum();
+
+        for player in players {
+            let stats = self.players.entry(player.name.clone()).or_default();
+
+            stats.games_played += 1;
+            stats.cards_left += player.card_num() as u32;
+            if player.won() {
+                stats.games_won += 1;
+                stats.points += points;
+            }
+        }
+    }
+
+    /// Players ordered by wins, then by points.
+    pub fn leaderboard(&self) -> Vec<(&String, &PlayerStats)> {
+        let mut result: Vec<_> = self.players.iter().collect();
+        result
+            .sort_by(|(_, a), (_, b)| b.games_won.cmp(&a.games_won).then(b.points.cmp(&a.points)));
+
+        result
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut result = String::from(
+            "name,games_played,games_won,points,average_cards_left,plus_fours_played\n",
+        );
+
+        for (name, stats) in self.leaderboard() {
+            result.push_str(&format!(
+                "{},{},{},{},{:.2},{}\n",
+                csv_field(name),
+                stats.games_played,
+                stats.games_won,
+                stats.points,
+                stats.average_cards_left(),
+                stats.plus_fours_played
+            ));
+        }
+
+        result
+    }
+}
+
+/// Quotes `value` for a CSV file if it has a comma, quote or line break in
+/// it, doubling any quotes.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,171 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use tui::{
+    buffer::Buffer,
+    layout::{Alignment, Rect},
+    style::{Modifier, Style},
+    text::Spans,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+use uno::cards::{Card, CardValue};
+
+use super::Theme;
+
+/// The size of a full-size card, border included.
+pub const CARD_WIDTH: u16 = 9;
+pub const CARD_HEIGHT: u16 = 7;
+
+/// The big picture in the middle of a card. Every row is as wide as the
+/// others so they line up when centered.
+fn glyph(value: CardValue) -> [&'static str; 3] {
+    match value {
+        CardValue::Num(0) => ["┏━┓", "┃ ┃", "┗━┛"],
+        CardValue::Num(1) => [" ┓ ", " ┃ ", " ┻ "],
+        CardValue::Num(2) => ["┏━┓", "┏━┛", "┗━━"],
+        CardValue::Num(3) => ["━━┓", " ━┫", "━━┛"],
+        CardValue::Num(4) => ["╻ ╻", "┗━┫", "  ╹"],
+        CardValue::Num(5) => ["┏━━", "┗━┓", "━━┛"],
+        CardValue::Num(6) => ["┏━━", "┣━┓", "┗━┛"],
+        CardValue::Num(7) => ["━━┓", "  ┃", "  ╹"],
+        CardValue::Num(8) => ["┏━┓", "┣━┫", "┗━┛"],
+        CardValue::Num(_) => ["┏━┓", "┗━┫", "━━┛"],
+        CardValue::PlusTwo => ["   ┏━┓", "╺╋╸┏━┛", "   ┗━━"],
+        CardValue::WildPlusFour => ["   ╻ ╻", "╺╋╸┗━┫", "     ╹"],
+        CardValue::Skip => ["╭─╮", "│╲│", "╰─╯"],
+        CardValue::Reverse => ["╭─<", "│ │", ">─╯"],
+        CardValue::Wild => ["╻   ╻", "┃ ╻ ┃", "┗━┻━┛"],
+    }
+}
+
+/// A card drawn at full size, with its value in two corners and a big
+/// glyph in the middle.
+pub struct CardArt<'a> {
+    card: &'a Card,
+    theme: &'a Theme,
+    selected: bool,
+    dimmed: bool,
+}
+
+impl<'a> CardArt<'a> {
+    pub fn new(card: &'a Card, theme: &'a Theme) -> Self {
+        CardArt {
+            card,
+            theme,
+            selected: false,
+            dimmed: false,
+        }
+    }
+
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Fades the card, for ones that can't be played.
+    pub fn dimmed(mut self, dimmed: bool) -> Self {
+        self.dimmed = dimmed;
+        self
+    }
+}
+
+impl Widget for CardArt<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut style = self.theme.style(self.card.color);
+        if self.dimmed {
+            style = style.add_modifier(Modifier::DIM);
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(if self.selected {
+                BorderType::Thick
+            } else {
+                BorderType::Rounded
+            })
+            .style(style);
+        let inner = block.inner(area);
+
+        // Cards in a fanned hand are drawn over each other.
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        if inner.height < 5 {
+            return;
+        }
+
+        let text = self.theme.text(self.card);
+        let corner = |y| Rect {
+            y,
+            height: 1,
+            ..inner
+        };
+        Paragraph::new(text.clone())
+            .style(style)
+            .render(corner(inner.y), buf);
+        Paragraph::new(text)
+            .style(style)
+            .alignment(Alignment::Right)
+            .render(corner(inner.bottom() - 1), buf);
+
+        let lines: Vec<Spans> = glyph(self.card.value)
+            .iter()
+            .map(|line| Spans::from(*line))
+            .collect();
+        let middle = Rect {
+            y: inner.y + (inner.height - 3) / 2,
+            height: 3,
+            ..inner
+        };
+        Paragraph::new(lines)
+            .style(style.add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .render(middle, buf);
+    }
+}
+
+/// The back of a card, for the draw pile.
+pub struct CardBack;
+
+impl Widget for CardBack {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let middle = Rect {
+            y: inner.y + inner.height / 2,
+            height: inner.height.min(1),
+            ..inner
+        };
+        Paragraph::new("UNO")
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .render(middle, buf);
+    }
+}
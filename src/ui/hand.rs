@@ -0,0 +1,77 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use uno::cards::Card;
+
+/// How the hand is laid out on screen. Only the client sorts; the server
+/// knows cards by their ids.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HandOrder {
+    Dealt,
+    Color,
+    Value,
+    Playable,
+}
+
+impl HandOrder {
+    /// The order the sort key switches to next.
+    pub fn next(self) -> Self {
+        match self {
+            HandOrder::Dealt => HandOrder::Color,
+            HandOrder::Color => HandOrder::Value,
+            HandOrder::Value => HandOrder::Playable,
+            HandOrder::Playable => HandOrder::Dealt,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HandOrder::Dealt => "as dealt",
+            HandOrder::Color => "by color",
+            HandOrder::Value => "by value",
+            HandOrder::Playable => "playable first",
+        }
+    }
+
+    /// The server's index of every card, in the order they're shown.
+    /// `playable` has the ids of the cards that can be played.
+    pub fn sort(self, cards: &[Card], playable: &[u16]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..cards.len()).collect();
+
+        match self {
+            HandOrder::Dealt => {}
+            HandOrder::Color => order.sort_by_key(|&i| (cards[i].color, cards[i].value)),
+            HandOrder::Value => order.sort_by_key(|&i| (cards[i].value, cards[i].color)),
+            HandOrder::Playable => order.sort_by_key(|&i| {
+                (
+                    !playable.contains(&cards[i].id),
+                    cards[i].color,
+                    cards[i].value,
+                )
+            }),
+        }
+
+        order
+    }
+}
@@ -0,0 +1,95 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use tui::layout::{Constraint, Direction, Layout, Rect};
+
+use super::card_art::CARD_HEIGHT;
+
+/// The smallest terminal the game screen is drawn in.
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 20;
+
+const TABLE_WIDTH: u16 = 25;
+
+/// The fewest rows left for the players and the discard pile before the
+/// controls pane gets collapsed.
+const MIN_TOP_HEIGHT: u16 = 6;
+
+/// A hand of one-line cards: two rows of them, a gap and the picker.
+const COMPACT_HAND_HEIGHT: u16 = 6;
+
+/// Where everything on the game screen goes.
+pub struct GameLayout {
+    pub table: Rect,
+    pub pile: Rect,
+    pub controls: Rect,
+    pub hand: Rect,
+    pub status: Rect,
+}
+
+impl GameLayout {
+    /// Splits the screen, or returns `None` if it's too small to play on.
+    /// Full-size cards are used when there's room for both the discard
+    /// pile and the hand, with a row for the selected card to stick out.
+    /// The controls pane is cut down to one row when it doesn't fit.
+    pub fn new(size: Rect, mut controls_height: u16) -> Option<Self> {
+        if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+            return None;
+        }
+
+        if size.height < MIN_TOP_HEIGHT + controls_height + COMPACT_HAND_HEIGHT + 1 {
+            controls_height = 1;
+        }
+
+        let hand_height = if size.height >= CARD_HEIGHT * 2 + controls_height + 5 {
+            CARD_HEIGHT + 4
+        } else {
+            COMPACT_HAND_HEIGHT
+        };
+
+        let rows = Layout::default()
+            .constraints(
+                [
+                    Constraint::Min(0),
+                    Constraint::Length(controls_height),
+                    Constraint::Length(hand_height),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+        let top = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(TABLE_WIDTH), Constraint::Min(0)].as_ref())
+            .split(rows[0]);
+
+        Some(GameLayout {
+            table: top[0],
+            pile: top[1],
+            controls: rows[1],
+            hand: rows[2],
+            status: rows[3],
+        })
+    }
+}
@@ -0,0 +1,1306 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+mod card_art;
+mod hand;
+mod layout;
+mod replay;
+mod theme;
+
+use card_art::{CardArt, CardBack, CARD_HEIGHT, CARD_WIDTH};
+use hand::HandOrder;
+use layout::{GameLayout, MIN_HEIGHT, MIN_WIDTH};
+pub use replay::ReplayUI;
+pub use theme::{Theme, ThemeName};
+
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+
+use crossterm::{
+    cursor::Show,
+    event::{
+        poll, read, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton,
+        MouseEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode},
+};
+
+use std::{
+    io, panic,
+    sync::Once,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::config::{KeyAction, Keymap};
+use uno::{
+    cards::{Card, CardColor},
+    client,
+    discovery::{Discovery, LanServer},
+    game,
+    player::Player,
+    stats::Stats,
+};
+
+use pad::PadStr;
+
+/// How long to wait between attempts to reach a server that stopped answering.
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Two clicks on the same card within this time play it.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// How long a toast stays in the status bar.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// How much of each card in a fanned hand has to show for it to be drawn
+/// full-size.
+const MIN_FAN_STEP: u16 = 3;
+
+const PICKER_COLORS: [CardColor; 4] = [
+    CardColor::Red,
+    CardColor::Green,
+    CardColor::Blue,
+    CardColor::Yellow,
+];
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), DisableMouseCapture, Show);
+}
+
+/// Keeps the terminal in raw mode for as long as it lives, and restores it
+/// when dropped or when the UI panics, so the shell isn't left garbled.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new() -> Self {
+        static HOOK: Once = Once::new();
+
+        HOOK.call_once(|| {
+            let default_hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                // The server may be running on another thread of this process.
+                if thread::current().name() == Some("main") {
+                    restore_terminal();
+                }
+                default_hook(info);
+            }));
+        });
+
+        enable_raw_mode().unwrap();
+        TerminalGuard
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_y) / 2),
+                Constraint::Percentage(percent_y),
+                Constraint::Percentage((100 - percent_y) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage((100 - percent_x) / 2),
+                Constraint::Percentage(percent_x),
+                Constraint::Percentage((100 - percent_x) / 2),
+            ]
+            .as_ref(),
+        )
+        .split(popup_layout[1])[1]
+}
+
+/// How many columns apart full-size cards go so `count` of them fit in
+/// `width`, or `None` if they'd overlap too much to tell apart.
+fn fan_step(count: u16, width: u16) -> Option<u16> {
+    if count <= 1 || count * (CARD_WIDTH + 1) - 1 <= width {
+        return Some(CARD_WIDTH + 1).filter(|_| width >= CARD_WIDTH);
+    }
+
+    let step = width.saturating_sub(CARD_WIDTH) / (count - 1);
+    Some(step).filter(|step| *step >= MIN_FAN_STEP)
+}
+
+/// Which way play is going, as shown to players.
+fn turn_arrow(direction: game::Direction) -> &'static str {
+    match direction {
+        game::Direction::Right => "↻ clockwise",
+        game::Direction::Left => "↺ counter-clockwise",
+    }
+}
+
+/// The cards waiting for the next player to draw, made hard to miss.
+fn plus_badge(plus: u8) -> Span<'static> {
+    Span::styled(
+        format!(" +{} to draw ", plus),
+        Style::default()
+            .bg(Color::Red)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+fn contains(rect: Rect, column: u16, row: u16) -> bool {
+    column >= rect.x && column < rect.right() && row >= rect.y && row < rect.bottom()
+}
+
+/// Everything the game screen shows, fetched from the server once per tick.
+struct Snapshot {
+    /// The hand in the order the server has it.
+    cards: Vec<Card>,
+    /// The server's index of every card, in the order they're shown.
+    order: Vec<usize>,
+    players: Vec<Player>,
+    top_card: Card,
+    plus: u8,
+    /// The ids of the cards that can be played.
+    playable: Vec<u16>,
+    current_turn: String,
+    next_player: String,
+    direction: game::Direction,
+}
+
+impl Snapshot {
+    /// The hand in the order it's shown.
+    fn hand(&self) -> Vec<Card> {
+        self.order.iter().map(|&index| self.cards[index]).collect()
+    }
+
+    /// The id of the card shown at `index`.
+    fn card_id(&self, index: usize) -> Option<u16> {
+        self.order.get(index).map(|&val| self.cards[val].id)
+    }
+
+    /// Where the card with `id` is shown.
+    fn position(&self, id: u16) -> Option<usize> {
+        self.order.iter().position(|&val| self.cards[val].id == id)
+    }
+
+    fn sort(&mut self, hand_order: HandOrder) {
+        self.order = hand_order.sort(&self.cards, &self.playable);
+    }
+
+    /// Which of the shown cards can't be played. Nothing is dimmed while
+    /// it's someone else's turn.
+    fn dimmed(&self, name: &str) -> Vec<bool> {
+        self.order
+            .iter()
+            .map(|&index| {
+                self.current_turn == name && !self.playable.contains(&self.cards[index].id)
+            })
+            .collect()
+    }
+
+    /// The first card shown after `index` that can be played, going back
+    /// around to the start.
+    fn next_playable(&self, index: usize) -> Option<usize> {
+        let len = self.order.len();
+        (1..=len)
+            .map(|offset| (index + offset) % len)
+            .find(|&val| self.playable.contains(&self.cards[self.order[val]].id))
+    }
+}
+
+/// Where the clickable parts of the game screen were last drawn.
+#[derive(Default)]
+struct Hitboxes {
+    cards: Vec<Rect>,
+    draw_pile: Rect,
+    colors: Vec<(CardColor, Rect)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum JoinField {
+    Name,
+    Address,
+}
+
+pub struct GameUI {
+    ticks: u64,
+    ip: String,
+    pub name: String,
+    selected_card: usize,
+    hand_order: HandOrder,
+    show_controls: bool,
+    client: client::Client,
+    keymap: Keymap,
+    theme: Theme,
+    winner: Option<Player>,
+    /// A short message for the status bar, and when it was shown.
+    toast: Option<(String, Instant)>,
+}
+
+impl GameUI {
+    pub fn new(ip: String, ticks: u64, keymap: Keymap, theme: Theme) -> Self {
+        GameUI {
+            ticks,
+            ip: ip.clone(),
+            name: String::new(),
+            selected_card: 0,
+            hand_order: HandOrder::Dealt,
+            show_controls: true,
+            client: client::Client::new(ip),
+            keymap,
+            theme,
+            winner: None,
+            toast: None,
+        }
+    }
+
+    pub fn join_screen(&mut self) -> bool {
+        let stdout = io::stdout();
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let _guard = TerminalGuard::new();
+
+        let ascii_art = "██╗   ██╗███╗   ██╗ ██████╗ \n██║   ██║████╗  ██║██╔═══██╗\n██║   ██║██╔██╗ ██║██║   ██║\n██║   ██║██║╚██╗██║██║   ██║\n╚██████╔╝██║ ╚████║╚██████╔╝\n ╚═════╝ ╚═╝  ╚═══╝ ╚═════╝ ";
+
+        let mut run = true;
+        let ticks = self.ticks;
+        let mut leaderboard: Option<Result<Stats, String>> = None;
+        let mut focus = JoinField::Name;
+        let mut error: Option<String> = None;
+
+        let mut discovery = Discovery::new().ok();
+        let mut selected_server: Option<usize> = None;
+
+        terminal.clear().unwrap();
+
+        while run {
+            let servers = match discovery.as_mut() {
+                Some(discovery) => {
+                    discovery.poll();
+                    discovery.servers().to_vec()
+                }
+                None => vec![],
+            };
+            selected_server = selected_server.filter(|&val| val < servers.len());
+
+            terminal
+                .draw(|f| {
+                    let size = f.size();
+
+                    f.render_widget(Clear, size);
+
+                    if let Some(stats) = &leaderboard {
+                        self.draw_leaderboard(f, stats);
+                        return;
+                    }
+
+                    let middle_rect = centered_rect(40, 80, size);
+
+                    let layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .margin(1)
+                        .constraints(
+                            [
+                                Constraint::Length(7),
+                                Constraint::Length(1),
+                                Constraint::Length(3),
+                                Constraint::Length(1),
+                                Constraint::Length(3),
+                                Constraint::Length(3),
+                                Constraint::Min(3),
+                            ]
+                            .as_ref(),
+                        )
+                        .split(middle_rect);
+
+                    let image = Paragraph::new(ascii_art)
+                        .alignment(Alignment::Center)
+                        .block(Block::default().borders(Borders::all()));
+
+                    let field_block = |field: JoinField| {
+                        let block = Block::default().borders(Borders::all());
+                        if field == focus {
+                            block.border_style(Style::default().add_modifier(Modifier::BOLD))
+                        } else {
+                            block
+                        }
+                    };
+
+                    let name_input = Paragraph::new(&self.name[..])
+                        .alignment(Alignment::Left)
+                        .block(field_block(JoinField::Name));
+
+                    let address_input = Paragraph::new(&self.ip[..])
+                        .alignment(Alignment::Left)
+                        .block(field_block(JoinField::Address));
+
+                    let name_text = Paragraph::new("Enter name").alignment(Alignment::Left);
+                    let address_text = Paragraph::new("Server address").alignment(Alignment::Left);
+
+                    let instruction = match &error {
+                        Some(e) => Paragraph::new(&e[..]).style(Style::default().fg(Color::Red)),
+                        None => {
+                            Paragraph::new("Enter - Join, Tab - Switch field, F2 - Leaderboard")
+                        }
+                    }
+                    .alignment(Alignment::Left)
+                    .block(Block::default().borders(Borders::ALL));
+
+                    f.render_widget(Block::default().borders(Borders::all()), middle_rect);
+                    f.render_widget(image, layout[0]);
+                    f.render_widget(name_text, layout[1]);
+                    f.render_widget(name_input, layout[2]);
+                    f.render_widget(address_text, layout[3]);
+                    f.render_widget(address_input, layout[4]);
+                    f.render_widget(instruction, layout[5]);
+                    self.draw_server_list(
+                        f,
+                        layout[6],
+                        &servers,
+                        selected_server,
+                        discovery.is_some(),
+                    );
+
+                    let (input, text) = match focus {
+                        JoinField::Name => (layout[2], &self.name),
+                        JoinField::Address => (layout[4], &self.ip),
+                    };
+                    f.set_cursor(input.x + 1 + text.chars().count() as u16, input.y + 1);
+                })
+                .unwrap();
+            if poll(Duration::from_millis(ticks)).unwrap() {
+                if let Event::Key(event) = read().unwrap() {
+                    use crossterm::event::KeyCode::*;
+
+                    if leaderboard.is_some() {
+                        if let Esc | F(2) = event.code {
+                            leaderboard = None;
+                        }
+                        continue;
+                    }
+
+                    match event.code {
+                        F(2) => {
+                            self.client = client::Client::new(self.ip.clone());
+                            leaderboard = Some(self.client.get_stats());
+                        }
+                        Tab | BackTab => {
+                            focus = match focus {
+                                JoinField::Name => JoinField::Address,
+                                JoinField::Address => JoinField::Name,
+                            };
+                        }
+                        Char(c) => match focus {
+                            JoinField::Name if self.name.len() <= 20 => self.name.push(c),
+                            JoinField::Address if self.ip.len() <= 64 => self.ip.push(c),
+                            _ => (),
+                        },
+                        Esc => {
+                            terminal.clear().unwrap();
+                            return true;
+                        }
+                        Backspace => {
+                            match focus {
+                                JoinField::Name => self.name.pop(),
+                                JoinField::Address => self.ip.pop(),
+                            };
+                        }
+                        Up | Down if !servers.is_empty() => {
+                            let selected = match (selected_server, event.code) {
+                                (None, Up) => servers.len() - 1,
+                                (None, _) => 0,
+                                (Some(val), Up) => val.saturating_sub(1),
+                                (Some(val), _) => (val + 1).min(servers.len() - 1),
+                            };
+                            selected_server = Some(selected);
+                            self.ip = servers[selected].address.clone();
+                        }
+                        Enter => {
+                            self.client = client::Client::new(self.ip.clone());
+                            error = None;
+
+                            if self.name.is_empty() {
+                                error = Some(String::from("Enter a name first"));
+                            } else if let Err(e) = self.client.check() {
+                                error = Some(format!("Couldn't connect: {}", e));
+                            } else {
+                                run = false;
+                            }
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        terminal.clear().unwrap();
+        false
+    }
+
+    fn draw_server_list(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        servers: &[LanServer],
+        selected: Option<usize>,
+        listening: bool,
+    ) {
+        let mut items = vec![];
+
+        for server in servers {
+            items.push(ListItem::new(format!(
+                "{} - {} player(s) - {}",
+                server.room, server.players, server.address
+            )));
+        }
+
+        let title = if listening {
+            "Servers (Up/Down to choose)"
+        } else {
+            "Servers (LAN discovery unavailable)"
+        };
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        state.select(selected);
+
+        f.render_stateful_widget(list, location, &mut state);
+    }
+
+    fn draw_leaderboard(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        stats: &Result<Stats, String>,
+    ) {
+        let area = centered_rect(70, 70, f.size());
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title("Leaderboard - Esc to go back");
+
+        let stats = match stats {
+            Ok(stats) => stats,
+            Err(e) => {
+                let p = Paragraph::new(format!("Couldn't get the leaderboard: {}", e))
+                    .alignment(Alignment::Center)
+                    .block(block);
+                f.render_widget(p, area);
+                return;
+            }
+        };
+
+        let mut rows = vec![];
+
+        for (name, player) in stats.leaderboard() {
+            rows.push(Row::new(vec![
+                name.clone(),
+                player.games_played.to_string(),
+                player.games_won.to_string(),
+                player.points.to_string(),
+                format!("{:.1}", player.average_cards_left()),
+                player.plus_fours_played.to_string(),
+            ]));
+        }
+
+        let widths = [
+            Constraint::Length(20),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(7),
+            Constraint::Length(11),
+            Constraint::Length(5),
+        ];
+        let table = Table::new(rows).block(block).widths(&widths).header(
+            Row::new(vec!["Name", "Played", "Won", "Points", "Avg. left", "+4s"])
+                .style(Style::default().add_modifier(Modifier::UNDERLINED)),
+        );
+        f.render_widget(table, area);
+    }
+
+    /// Draws the discard pile with the draw pile next to it at the bottom
+    /// of `location`, and returns where the draw pile is.
+    fn draw_top_card(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        last_card: &Card,
+        plus: u8,
+    ) -> Rect {
+        if location.height < CARD_HEIGHT || location.width < CARD_WIDTH * 3 + 2 {
+            return self.draw_top_card_compact(f, location, last_card);
+        }
+
+        let area = Rect::new(
+            location.x + (location.width - CARD_WIDTH) / 2,
+            location.bottom() - CARD_HEIGHT,
+            CARD_WIDTH,
+            CARD_HEIGHT,
+        );
+        let pile_area = Rect {
+            x: area.x.saturating_sub(CARD_WIDTH + 2),
+            ..area
+        };
+
+        f.render_widget(CardArt::new(last_card, &self.theme), area);
+        f.render_widget(CardBack, pile_area);
+
+        if plus > 0 && area.y > location.y {
+            let badge = Rect {
+                y: area.y - 1,
+                height: 1,
+                ..location
+            };
+            f.render_widget(
+                Paragraph::new(plus_badge(plus)).alignment(Alignment::Center),
+                badge,
+            );
+        }
+
+        pile_area
+    }
+
+    /// The discard pile as a small colored block, for terminals too short
+    /// for full-size cards.
+    fn draw_top_card_compact(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        last_card: &Card,
+    ) -> Rect {
+        let (width, height) = (8, location.height.min(5));
+        let area = Rect::new(
+            location.x + location.width.saturating_sub(width) / 2,
+            location.bottom() - height,
+            width,
+            height,
+        )
+        .intersection(location);
+
+        f.render_widget(
+            Block::default().style(self.theme.style(last_card.color)),
+            area,
+        );
+
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints(
+                [
+                    Constraint::Length(1),
+                    Constraint::Min(0),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
+            .split(area);
+
+        let text1 = Paragraph::new(self.theme.text(last_card)).alignment(Alignment::Left);
+
+        let text2 = Paragraph::new(self.theme.text(last_card)).alignment(Alignment::Right);
+        f.render_widget(text1, layout[0]);
+        f.render_widget(text2, layout[2]);
+
+        let pile_area = Rect {
+            x: area.x.saturating_sub(area.width + 2),
+            ..area
+        };
+        let pile = Paragraph::new("UNO")
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(pile, pile_area);
+
+        pile_area
+    }
+
+    /// Draws the hand, and the color picker when a wild card is selected.
+    /// Returns where every card and every color was drawn.
+    fn draw_player_cards(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        cards: &[Card],
+        dimmed: &[bool],
+    ) -> (Vec<Rect>, Vec<(CardColor, Rect)>) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Hand, {}", self.hand_order.name()));
+        let inner = block.inner(location);
+        f.render_widget(block, location);
+
+        // The selected card sticks out one row above the rest, and the
+        // picker goes under them.
+        let step = fan_step(cards.len() as u16, inner.width);
+        let (card_rects, picker_y) = match step {
+            Some(step) if inner.height >= CARD_HEIGHT + 2 => (
+                self.draw_hand(f, inner, cards, dimmed, step),
+                inner.y + CARD_HEIGHT + 1,
+            ),
+            _ => {
+                // Keep a gap and a row for the picker under the hand.
+                let rows = match inner.height {
+                    0..=2 => inner.height,
+                    height => height - 2,
+                };
+                let area = Rect {
+                    height: rows,
+                    ..inner
+                };
+                (
+                    self.draw_hand_compact(f, area, cards, dimmed),
+                    inner.y + rows + 1,
+                )
+            }
+        };
+
+        let mut color_rects = vec![];
+
+        match cards.get(self.selected_card) {
+            Some(card) if card.is_wild() && picker_y < inner.bottom() => {
+                let label = "Color: ";
+                let color_width = 8;
+                let width = label.len() as u16 + PICKER_COLORS.len() as u16 * color_width;
+                let mut x = inner.x + inner.width.saturating_sub(width) / 2;
+                let y = picker_y;
+
+                f.render_widget(
+                    Paragraph::new(label),
+                    Rect::new(x, y, label.len() as u16, 1).intersection(inner),
+                );
+                x += label.len() as u16;
+
+                for color in PICKER_COLORS.iter() {
+                    let mut style = self.theme.style(*color);
+                    if card.color == *color {
+                        style = style.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+                    }
+
+                    let rect = Rect::new(x, y, color_width - 1, 1).intersection(inner);
+                    let text = format!("{:?}", color).pad_to_width(color_width as usize - 1);
+
+                    f.render_widget(Paragraph::new(Span::styled(text, style)), rect);
+                    color_rects.push((*color, rect));
+                    x += color_width;
+                }
+            }
+            _ => (),
+        }
+
+        (card_rects, color_rects)
+    }
+
+    /// Draws the hand as full-size cards `step` columns apart, which
+    /// overlaps them when the hand is too wide to lay out side by side.
+    /// Returns the part of each card that can be seen.
+    fn draw_hand(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        inner: Rect,
+        cards: &[Card],
+        dimmed: &[bool],
+        step: u16,
+    ) -> Vec<Rect> {
+        let line_width = (cards.len() as u16).saturating_sub(1) * step + CARD_WIDTH;
+        let start = inner.x + inner.width.saturating_sub(line_width) / 2;
+        let selected = self.selected_card.min(cards.len().saturating_sub(1));
+
+        let rects: Vec<Rect> = (0..cards.len())
+            .map(|index| {
+                let raised = index == selected;
+                Rect::new(
+                    start + index as u16 * step,
+                    inner.y + if raised { 0 } else { 1 },
+                    CARD_WIDTH,
+                    CARD_HEIGHT,
+                )
+            })
+            .collect();
+
+        // The selected card goes on top of the ones next to it.
+        let art = |index: usize| {
+            CardArt::new(&cards[index], &self.theme)
+                .dimmed(dimmed.get(index).copied().unwrap_or(false))
+        };
+        for (index, rect) in rects.iter().enumerate() {
+            if index != selected {
+                f.render_widget(art(index), *rect);
+            }
+        }
+        if let Some(rect) = rects.get(selected) {
+            f.render_widget(art(selected).selected(true), *rect);
+        }
+
+        let selected_right = rects.get(selected).map_or(0, |rect| rect.right());
+        (0..rects.len())
+            .map(|index| {
+                let rect = rects[index];
+                let mut left = rect.x;
+                let mut right = match rects.get(index + 1) {
+                    Some(next) => next.x.min(rect.right()),
+                    None => rect.right(),
+                };
+                if index == selected {
+                    right = rect.right();
+                } else if index > selected {
+                    left = left.max(selected_right);
+                }
+                right = right.max(left);
+
+                Rect::new(left, rect.y, right - left, rect.height)
+            })
+            .collect()
+    }
+
+    /// Draws the hand as rows of colored labels, for terminals too small
+    /// for full-size cards. Hands with more rows than fit scroll to keep
+    /// the selected card in view, and cards that are scrolled away get an
+    /// empty rect.
+    fn draw_hand_compact(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        area: Rect,
+        cards: &[Card],
+        dimmed: &[bool],
+    ) -> Vec<Rect> {
+        let card_width = self.theme.card_width() as u16;
+        let step = card_width + 1;
+        if area.height == 0 || area.width < step + 1 {
+            return vec![Rect::default(); cards.len()];
+        }
+
+        // The last column is kept for the scroll arrows.
+        let per_row = (area.width / step) as usize;
+        let rows = cards.len().div_ceil(per_row);
+        let visible = area.height as usize;
+        let first_row = (self.selected_card / per_row).saturating_sub(visible - 1);
+
+        let line_width = (cards.len().min(per_row) as u16 * step).saturating_sub(1);
+        let start = area.x + (area.width - 1).saturating_sub(line_width) / 2;
+        let mut card_rects = vec![];
+
+        for (index, card) in cards.iter().enumerate() {
+            let row = index / per_row;
+            if row < first_row || row >= first_row + visible {
+                card_rects.push(Rect::default());
+                continue;
+            }
+
+            let x = start + (index % per_row) as u16 * step;
+            let y = area.y + (row - first_row) as u16;
+
+            let mut card_style = self.theme.style(card.color);
+
+            if index == self.selected_card {
+                card_style = card_style.add_modifier(Modifier::UNDERLINED);
+            }
+            if dimmed.get(index).copied().unwrap_or(false) {
+                card_style = card_style.add_modifier(Modifier::DIM);
+            }
+
+            let rect = Rect::new(x, y, card_width, 1);
+            let card_text = Span::styled(self.theme.label(card), card_style);
+
+            f.render_widget(Paragraph::new(card_text), rect);
+            card_rects.push(rect);
+        }
+
+        let arrow = |y| Rect::new(area.right() - 1, y, 1, 1);
+        if first_row > 0 {
+            f.render_widget(Paragraph::new("▲"), arrow(area.y));
+        }
+        if first_row + visible < rows {
+            f.render_widget(Paragraph::new("▼"), arrow(area.bottom() - 1));
+        }
+
+        card_rects
+    }
+
+    /// Draws whose turn it is, who's next, any cards waiting to be drawn
+    /// and the latest toast if it hasn't expired.
+    fn draw_status_bar(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        snapshot: &Snapshot,
+    ) {
+        let mut status = vec![Span::raw(format!(
+            "Current turn: {}   Next: {}   {}",
+            snapshot.current_turn,
+            snapshot.next_player,
+            turn_arrow(snapshot.direction)
+        ))];
+        if snapshot.plus > 0 {
+            status.push(Span::raw("   "));
+            status.push(plus_badge(snapshot.plus));
+        }
+
+        let p = Paragraph::new(Spans::from(status));
+
+        f.render_widget(p, location);
+
+        if let Some((message, time)) = &self.toast {
+            if time.elapsed() < TOAST_DURATION {
+                let toast = Paragraph::new(Span::styled(
+                    message.as_str(),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ))
+                .alignment(Alignment::Right);
+                f.render_widget(toast, location);
+            }
+        }
+    }
+
+    /// The lines of the controls pane, or the one line left when it's
+    /// collapsed.
+    fn controls(&self) -> Vec<Spans<'static>> {
+        if !self.show_controls {
+            return vec![Spans::from(format!(
+                "{} - Show controls",
+                self.keymap.keys(KeyAction::Controls)
+            ))];
+        }
+
+        let controls = [
+            (
+                vec![KeyAction::Right, KeyAction::Left],
+                "Card Choosing (or click a card)",
+            ),
+            (
+                vec![KeyAction::ColorUp, KeyAction::ColorDown],
+                "Cycle colors for card (or click a color)",
+            ),
+            (vec![KeyAction::Play], "Use card (or double-click it)"),
+            (vec![KeyAction::Hint], "Jump to a card you can play"),
+            (vec![KeyAction::Draw], "Take card (or click the pile)"),
+            (vec![KeyAction::Sort], "Sort hand"),
+            (vec![KeyAction::Save], "Save game"),
+            (vec![KeyAction::Controls], "Hide controls"),
+            (vec![KeyAction::Quit], "Quit"),
+        ];
+
+        controls
+            .iter()
+            .map(|(actions, description)| {
+                let keys: Vec<String> = actions
+                    .iter()
+                    .map(|action| self.keymap.keys(*action))
+                    .collect();
+                Spans::from(format!("{} - {}", keys.join("/"), description))
+            })
+            .collect()
+    }
+
+    fn draw_controls(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, location: Rect) {
+        let mut lines = self.controls();
+        if lines.len() > location.height as usize {
+            lines = vec![Spans::from(format!(
+                "{} - Controls (the window is too short for them)",
+                self.keymap.keys(KeyAction::Controls)
+            ))];
+        }
+
+        let p = Paragraph::new(lines).alignment(Alignment::Center);
+
+        f.render_widget(p, location);
+    }
+
+    /// Draws the players with the current one highlighted and the next
+    /// one marked, under which way play is going.
+    fn draw_player_table(
+        &self,
+        f: &mut Frame<CrosstermBackend<io::Stdout>>,
+        location: Rect,
+        snapshot: &Snapshot,
+    ) {
+        let (name_space, number_space) = (15, 8);
+        // Only as tall as the players need, below a one-row margin.
+        let location = Rect {
+            x: location.x + 1,
+            y: location.y + 1,
+            width: (name_space + number_space).min(location.width.saturating_sub(1)),
+            height: (snapshot.players.len() as u16 + 3).min(location.height.saturating_sub(1)),
+        };
+
+        let mut rows = vec![];
+
+        for player in snapshot.players.iter() {
+            let (marker, style) = if player.name == snapshot.current_turn {
+                (
+                    "▶ ",
+                    Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED),
+                )
+            } else if player.name == snapshot.next_player {
+                ("› ", Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                ("  ", Style::default())
+            };
+
+            rows.push(
+                Row::new(vec![
+                    format!("{}{}", marker, player.name),
+                    player.card_num().to_string(),
+                ])
+                .style(style),
+            );
+        }
+
+        let widths = [
+            Constraint::Length(name_space),
+            Constraint::Length(number_space),
+        ];
+        let table = Table::new(rows)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(turn_arrow(snapshot.direction)),
+            )
+            .widths(&widths)
+            .header(
+                Row::new(vec!["Name", "Cards"])
+                    .style(Style::default().add_modifier(Modifier::UNDERLINED)),
+            );
+        f.render_widget(table, location);
+    }
+
+    fn draw_too_small(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>) {
+        let size = f.size();
+        let text = vec![
+            Spans::from(Span::styled(
+                "Terminal too small",
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(format!(
+                "Needs {}x{}, is {}x{}",
+                MIN_WIDTH, MIN_HEIGHT, size.width, size.height
+            )),
+        ];
+        let area = Rect {
+            y: size.height.saturating_sub(2) / 2,
+            height: size.height.min(2),
+            ..size
+        };
+
+        f.render_widget(Paragraph::new(text).alignment(Alignment::Center), area);
+    }
+
+    fn draw_winner(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, winner: &Player) {
+        let area = centered_rect(50, 40, f.size());
+        let area = Layout::default()
+            .constraints([
+                Constraint::Percentage(40),
+                Constraint::Length(1),
+                Constraint::Percentage(100),
+            ])
+            .split(area)[1];
+        let p = Paragraph::new(format!("{} won!", winner.name))
+            .alignment(Alignment::Center)
+            .style(Style::default().add_modifier(Modifier::UNDERLINED));
+        f.render_widget(p, area);
+    }
+
+    fn draw_connection_lost(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, attempts: u32) {
+        let area = centered_rect(40, 20, f.size());
+        let p = Paragraph::new(vec![
+            Spans::from("Connection lost, retrying…"),
+            Spans::from(format!("Attempt {} - Esc to quit", attempts)),
+        ])
+        .alignment(Alignment::Center)
+        .style(Style::default().add_modifier(Modifier::BOLD))
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(Clear, area);
+        f.render_widget(p, area);
+    }
+
+    fn draw(&mut self) {
+        let result = self.client.draw(&self.name);
+        self.show_error(result);
+    }
+
+    /// Shows the error in a toast if `result` is one.
+    fn show_error(&mut self, result: Result<(), String>) {
+        if let Err(e) = result {
+            self.toast = Some((e, Instant::now()));
+        }
+    }
+
+    /// Gets everything the game screen shows from the server.
+    fn fetch(&self) -> Result<Snapshot, String> {
+        let mut snapshot = Snapshot {
+            cards: self.client.get_cards_for(&self.name)?,
+            order: vec![],
+            players: self.client.get_players()?,
+            top_card: self.client.top_card()?,
+            playable: self.client.playable_cards(&self.name)?,
+            plus: self.client.get_plus()?,
+            current_turn: self.client.current_turn()?,
+            next_player: self.client.next_player()?,
+            direction: self.client.get_direction()?,
+        };
+        snapshot.sort(self.hand_order);
+
+        Ok(snapshot)
+    }
+
+    pub fn game_screen(&mut self) {
+        let stdout = io::stdout();
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal.clear().unwrap();
+
+        let _guard = TerminalGuard::new();
+        execute!(io::stdout(), EnableMouseCapture).unwrap();
+
+        let ticks = self.ticks;
+
+        let mut run = true;
+        let mut snapshot: Option<Snapshot> = None;
+        let mut hitboxes = Hitboxes::default();
+        let mut last_click: Option<(usize, Instant)> = None;
+        // Failed attempts since the connection was lost, and when the last one was made.
+        let mut attempts = 0;
+        let mut last_attempt: Option<Instant> = None;
+        let mut joined = false;
+
+        while run {
+            let retry = match last_attempt {
+                Some(time) if attempts > 0 => time.elapsed() >= RETRY_INTERVAL,
+                _ => true,
+            };
+
+            if retry {
+                last_attempt = Some(Instant::now());
+
+                // Joining again after a reconnect gets the seat back.
+                let result = if joined {
+                    self.fetch()
+                } else {
+                    self.client.join(&self.name).and_then(|_| self.fetch())
+                };
+
+                match result {
+                    Ok(new_snapshot) => {
+                        // Stay on the same card when the hand changes under it.
+                        let selected = snapshot
+                            .as_ref()
+                            .and_then(|val| val.card_id(self.selected_card))
+                            .and_then(|id| new_snapshot.position(id));
+                        if let Some(index) = selected {
+                            self.selected_card = index;
+                        } else if new_snapshot.cards.is_empty() {
+                            self.selected_card = 0;
+                        } else if self.selected_card >= new_snapshot.cards.len() {
+                            self.selected_card = new_snapshot.cards.len() - 1;
+                        }
+
+                        self.winner = new_snapshot.players.iter().find(|val| val.won()).cloned();
+                        snapshot = Some(new_snapshot);
+                        attempts = 0;
+                        joined = true;
+                    }
+                    Err(_) => {
+                        attempts += 1;
+                        joined = false;
+                    }
+                }
+            }
+
+            terminal
+                .draw(|f| {
+                    match (&snapshot, &self.winner) {
+                        (_, Some(w)) => self.draw_winner(f, w),
+                        (Some(snapshot), None) => {
+                            f.render_widget(Clear, f.size());
+
+                            let controls_height = if self.show_controls {
+                                self.controls().len() as u16 + 1
+                            } else {
+                                1
+                            };
+                            let layout = match GameLayout::new(f.size(), controls_height) {
+                                Some(layout) => layout,
+                                None => {
+                                    hitboxes = Hitboxes::default();
+                                    self.draw_too_small(f);
+                                    return;
+                                }
+                            };
+
+                            hitboxes.draw_pile = self.draw_top_card(
+                                f,
+                                layout.pile,
+                                &snapshot.top_card,
+                                snapshot.plus,
+                            );
+                            self.draw_controls(f, layout.controls);
+                            let (cards, colors) = self.draw_player_cards(
+                                f,
+                                layout.hand,
+                                &snapshot.hand(),
+                                &snapshot.dimmed(&self.name),
+                            );
+                            hitboxes.cards = cards;
+                            hitboxes.colors = colors;
+                            self.draw_status_bar(f, layout.status, snapshot);
+                            self.draw_player_table(f, layout.table, snapshot);
+                        }
+                        (None, None) => f.render_widget(Clear, f.size()),
+                    }
+
+                    if attempts > 0 {
+                        self.draw_connection_lost(f, attempts);
+                    }
+                })
+                .unwrap();
+
+            if poll(Duration::from_millis(ticks)).unwrap() {
+                let event = read().unwrap();
+                let playing = self.winner.is_none() && attempts == 0;
+
+                if let Event::Mouse(event) = event {
+                    if playing && event.kind == MouseEventKind::Down(MouseButton::Left) {
+                        let (column, row) = (event.column, event.row);
+                        let card_id = |index| snapshot.as_ref().and_then(|val| val.card_id(index));
+                        let card = hitboxes
+                            .cards
+                            .iter()
+                            .position(|rect| contains(*rect, column, row));
+                        let color = hitboxes
+                            .colors
+                            .iter()
+                            .find(|(_, rect)| contains(*rect, column, row));
+
+                        if let Some(index) = card {
+                            let double_click = matches!(last_click,
+                                Some((last, time)) if last == index && time.elapsed() < DOUBLE_CLICK);
+
+                            self.selected_card = index;
+                            if let (true, Some(id)) = (double_click, card_id(index)) {
+                                let result = self.client.use_card(&self.name, id);
+                                self.show_error(result);
+                                last_click = None;
+                            } else {
+                                last_click = Some((index, Instant::now()));
+                            }
+                        } else if let Some((color, _)) = color {
+                            if let Some(id) = card_id(self.selected_card) {
+                                let _ = self.client.set_color(&self.name, id, *color);
+                            }
+                        } else if contains(hitboxes.draw_pile, column, row) {
+                            self.draw();
+                        }
+                    }
+                }
+
+                if let Event::Key(event) = event {
+                    let action = self.keymap.action(event.code);
+
+                    if playing {
+                        // Errors here show up as a lost connection on the next fetch.
+                        let card_num = snapshot.as_ref().map_or(0, |val| val.cards.len());
+                        let selected = snapshot
+                            .as_ref()
+                            .and_then(|val| val.card_id(self.selected_card));
+
+                        match action {
+                            Some(KeyAction::Draw) => self.draw(),
+                            Some(KeyAction::Save) => {
+                                let result = self.client.save();
+                                self.show_error(result);
+                            }
+                            Some(KeyAction::Quit) => run = false,
+                            Some(KeyAction::Left) if self.selected_card > 0 => {
+                                self.selected_card -= 1;
+                            }
+                            Some(KeyAction::Right) if self.selected_card + 1 < card_num => {
+                                self.selected_card += 1;
+                            }
+                            Some(KeyAction::ColorUp) => {
+                                if let Some(id) = selected {
+                                    let _ = self.client.cycle_color_up(&self.name, id);
+                                }
+                            }
+                            Some(KeyAction::ColorDown) => {
+                                if let Some(id) = selected {
+                                    let _ = self.client.cycle_color_down(&self.name, id);
+                                }
+                            }
+                            Some(KeyAction::Play) => {
+                                if let Some(id) = selected {
+                                    let result = self.client.use_card(&self.name, id);
+                                    self.show_error(result);
+                                }
+                            }
+                            Some(KeyAction::Hint) => {
+                                match snapshot
+                                    .as_ref()
+                                    .and_then(|val| val.next_playable(self.selected_card))
+                                {
+                                    Some(index) => self.selected_card = index,
+                                    None => self.show_error(Err(String::from(
+                                        "Nothing to play, take a card",
+                                    ))),
+                                }
+                            }
+                            Some(KeyAction::Controls) => {
+                                self.show_controls = !self.show_controls;
+                            }
+                            Some(KeyAction::Sort) => {
+                                // Keep the same card selected after sorting.
+                                self.hand_order = self.hand_order.next();
+                                if let Some(snapshot) = snapshot.as_mut() {
+                                    snapshot.sort(self.hand_order);
+                                    self.selected_card =
+                                        selected.and_then(|id| snapshot.position(id)).unwrap_or(0);
+                                }
+                            }
+                            _ => (),
+                        }
+                    } else if action == Some(KeyAction::Quit) || event.code == KeyCode::Esc {
+                        run = false;
+                    }
+                }
+            }
+        }
+        terminal.clear().unwrap();
+    }
+}
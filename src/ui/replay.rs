@@ -0,0 +1,284 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use tui::{
+    backend::CrosstermBackend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Paragraph},
+    Frame, Terminal,
+};
+
+use crossterm::event::{poll, read, Event};
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use uno::{
+    game::{Action, Game},
+    replay::Replay,
+};
+
+use super::{plus_badge, turn_arrow, TerminalGuard, Theme};
+
+use pad::PadStr;
+
+const SPEEDS: [f64; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+
+pub struct ReplayUI {
+    ticks: u64,
+    theme: Theme,
+    replay: Replay,
+    game: Game,
+    position: usize,
+    playing: bool,
+    speed: usize,
+    last_step: Instant,
+}
+
+impl ReplayUI {
+    pub fn new(replay: Replay, ticks: u64, theme: Theme) -> Self {
+        ReplayUI {
+            ticks,
+            theme,
+            game: replay.game_at(0),
+            replay,
+            position: 0,
+            playing: true,
+            speed: 2,
+            last_step: Instant::now(),
+        }
+    }
+
+    fn step_forward(&mut self) {
+        if self.position < self.replay.steps.len() {
+            let _ = self.game.apply(&self.replay.steps[self.position].action);
+            self.position += 1;
+        }
+        self.last_step = Instant::now();
+    }
+
+    fn step_back(&mut self) {
+        if self.position > 0 {
+            self.position -= 1;
+            self.game = self.replay.game_at(self.position);
+        }
+        self.last_step = Instant::now();
+    }
+
+    /// How long to wait before playing the next step at the current speed.
+    fn next_delay(&self) -> Option<Duration> {
+        let next = self.replay.steps.get(self.position)?;
+        let previous = match self.position {
+            0 => 0,
+            n => self.replay.steps[n - 1].time,
+        };
+        let millis = next.time.saturating_sub(previous) as f64 / SPEEDS[self.speed];
+
+        Some(Duration::from_millis(millis as u64))
+    }
+
+    fn describe(action: &Action) -> String {
+        match action {
+            Action::Join { name } => format!("{} joined", name),
+            Action::Draw { name } => format!("{} drew", name),
+            Action::UseCard { name, card_id, .. } => {
+                format!("{} played card #{}", name, card_id)
+            }
+            Action::CycleColorUp { name, .. }
+            | Action::CycleColorDown { name, .. }
+            | Action::SetColor { name, .. } => format!("{} picked a color", name),
+        }
+    }
+
+    fn draw_status(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, location: Rect) {
+        let time = match self.position {
+            0 => 0,
+            n => self.replay.steps[n - 1].time / 1000,
+        };
+        let last_action = match self.position {
+            0 => String::from("Start of game"),
+            n => ReplayUI::describe(&self.replay.steps[n - 1].action),
+        };
+
+        let status = format!(
+            "{} Step {}/{}  {:02}:{:02}  x{}  -  {}",
+            if self.playing { "▶" } else { "⏸" },
+            self.position,
+            self.replay.steps.len(),
+            time / 60,
+            time % 60,
+            SPEEDS[self.speed],
+            last_action
+        );
+
+        let p = Paragraph::new(status).block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(p, location);
+    }
+
+    fn draw_top_card(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, location: Rect) {
+        let card = self.game.last_card;
+        let style = self.theme.style(card.color);
+
+        let mut spans = vec![
+            Span::raw("Top card: "),
+            Span::styled(self.theme.label(&card), style),
+            Span::raw(format!("   {}", turn_arrow(self.game.direction()))),
+        ];
+        if self.game.plus > 0 {
+            spans.push(Span::raw("   "));
+            spans.push(plus_badge(self.game.plus));
+        }
+
+        let p = Paragraph::new(Spans::from(spans)).alignment(Alignment::Center);
+
+        f.render_widget(p, location);
+    }
+
+    fn draw_hands(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, location: Rect) {
+        let mut lines = vec![];
+
+        let next = match self.game.players.len() {
+            0 => None,
+            _ => Some(self.game.next_player()),
+        };
+
+        for (index, player) in self.game.players.iter().enumerate() {
+            let mut name_style = Style::default();
+            let marker = if index as u8 == self.game.current_turn {
+                name_style = name_style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                "▶ "
+            } else if Some(index as u8) == next {
+                name_style = name_style.add_modifier(Modifier::BOLD);
+                "› "
+            } else {
+                "  "
+            };
+
+            let mut spans = vec![Span::styled(
+                format!("{}{} ({}) ", marker, player.name, player.card_num()).pad_to_width(20),
+                name_style,
+            )];
+
+            for card in player.cards.iter() {
+                spans.push(Span::styled(
+                    self.theme.label(card),
+                    self.theme.style(card.color),
+                ));
+                spans.push(Span::raw(" "));
+            }
+
+            lines.push(Spans::from(spans));
+            lines.push(Spans::from(""));
+        }
+
+        let p = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Hands"));
+
+        f.render_widget(p, location);
+    }
+
+    fn draw_controls(&self, f: &mut Frame<CrosstermBackend<io::Stdout>>, location: Rect) {
+        let p =
+            Paragraph::new("Space - Pause/Play   Right/Left - Step   Up/Down - Speed   Esc - Quit")
+                .alignment(Alignment::Center);
+
+        f.render_widget(p, location);
+    }
+
+    pub fn replay_screen(&mut self) {
+        let stdout = io::stdout();
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal.clear().unwrap();
+
+        let _guard = TerminalGuard::new();
+
+        let ticks = self.ticks;
+
+        let mut run = true;
+
+        while run {
+            terminal
+                .draw(|f| {
+                    let layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            [
+                                Constraint::Length(3),
+                                Constraint::Length(1),
+                                Constraint::Min(3),
+                                Constraint::Length(1),
+                            ]
+                            .as_ref(),
+                        )
+                        .split(f.size());
+
+                    self.draw_status(f, layout[0]);
+                    self.draw_top_card(f, layout[1]);
+                    self.draw_hands(f, layout[2]);
+                    self.draw_controls(f, layout[3]);
+                })
+                .unwrap();
+
+            if self.playing {
+                match self.next_delay() {
+                    Some(delay) if self.last_step.elapsed() >= delay => self.step_forward(),
+                    Some(_) => (),
+                    None => self.playing = false,
+                }
+            }
+
+            if poll(Duration::from_millis(ticks)).unwrap() {
+                if let Event::Key(event) = read().unwrap() {
+                    use crossterm::event::KeyCode::*;
+
+                    match event.code {
+                        Esc => run = false,
+                        Char(' ') => {
+                            self.playing = !self.playing;
+                            self.last_step = Instant::now();
+                        }
+                        Right => {
+                            self.playing = false;
+                            self.step_forward();
+                        }
+                        Left => {
+                            self.playing = false;
+                            self.step_back();
+                        }
+                        Up if self.speed < SPEEDS.len() - 1 => self.speed += 1,
+                        Down if self.speed > 0 => self.speed -= 1,
+                        _ => (),
+                    }
+                }
+            }
+        }
+        terminal.clear().unwrap();
+    }
+}
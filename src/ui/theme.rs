@@ -0,0 +1,178 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::env;
+
+use serde::{Deserialize, Serialize};
+use tui::style::{Color, Modifier, Style};
+
+use uno::cards::{Card, CardColor};
+
+use pad::PadStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    Default,
+    HighContrast,
+    Colorblind,
+}
+
+impl ThemeName {
+    pub const NAMES: [&'static str; 3] = ["default", "high-contrast", "colorblind"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(ThemeName::Default),
+            "high-contrast" => Some(ThemeName::HighContrast),
+            "colorblind" => Some(ThemeName::Colorblind),
+            _ => None,
+        }
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ColorDepth {
+    Basic,
+    Indexed,
+    TrueColor,
+}
+
+impl ColorDepth {
+    fn detect() -> Self {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Indexed
+        } else {
+            ColorDepth::Basic
+        }
+    }
+}
+
+/// How cards are colored and labeled.
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    name: ThemeName,
+    depth: ColorDepth,
+}
+
+impl Theme {
+    pub fn new(name: ThemeName) -> Self {
+        Theme {
+            name,
+            depth: ColorDepth::detect(),
+        }
+    }
+
+    /// Whether cards carry a letter for their color, so they can be told
+    /// apart without seeing it.
+    fn symbols(&self) -> bool {
+        self.name != ThemeName::Default
+    }
+
+    fn color(&self, color: CardColor) -> Color {
+        use CardColor::*;
+
+        // The colorblind palette is Okabe and Ito's.
+        match (self.name, self.depth, color) {
+            (_, _, None) => Color::Reset,
+            (ThemeName::Colorblind, ColorDepth::TrueColor, Red) => Color::Rgb(213, 94, 0),
+            (ThemeName::Colorblind, ColorDepth::TrueColor, Green) => Color::Rgb(0, 158, 115),
+            (ThemeName::Colorblind, ColorDepth::TrueColor, Blue) => Color::Rgb(0, 114, 178),
+            (ThemeName::Colorblind, ColorDepth::TrueColor, Yellow) => Color::Rgb(240, 228, 66),
+            (ThemeName::Colorblind, ColorDepth::Indexed, Red) => Color::Indexed(166),
+            (ThemeName::Colorblind, ColorDepth::Indexed, Green) => Color::Indexed(36),
+            (ThemeName::Colorblind, ColorDepth::Indexed, Blue) => Color::Indexed(25),
+            (ThemeName::Colorblind, ColorDepth::Indexed, Yellow) => Color::Indexed(227),
+            (ThemeName::HighContrast, ColorDepth::Basic, Red) => Color::LightRed,
+            (ThemeName::HighContrast, ColorDepth::Basic, Green) => Color::LightGreen,
+            (ThemeName::HighContrast, ColorDepth::Basic, Blue) => Color::LightBlue,
+            (ThemeName::HighContrast, ColorDepth::Basic, Yellow) => Color::LightYellow,
+            (_, ColorDepth::TrueColor, Red) => Color::Rgb(215, 38, 56),
+            (_, ColorDepth::TrueColor, Green) => Color::Rgb(55, 151, 17),
+            (_, ColorDepth::TrueColor, Blue) => Color::Rgb(9, 86, 191),
+            (_, ColorDepth::TrueColor, Yellow) => Color::Rgb(236, 212, 7),
+            (_, ColorDepth::Indexed, Red) => Color::Indexed(160),
+            (_, ColorDepth::Indexed, Green) => Color::Indexed(34),
+            (_, ColorDepth::Indexed, Blue) => Color::Indexed(26),
+            (_, ColorDepth::Indexed, Yellow) => Color::Indexed(220),
+            (_, ColorDepth::Basic, Red) => Color::Red,
+            (_, ColorDepth::Basic, Green) => Color::Green,
+            (_, ColorDepth::Basic, Blue) => Color::Blue,
+            (_, ColorDepth::Basic, Yellow) => Color::Yellow,
+        }
+    }
+
+    /// The style of something shown in a card's color.
+    pub fn style(&self, color: CardColor) -> Style {
+        let style = Style::default().bg(self.color(color));
+
+        match (self.name, color) {
+            (_, CardColor::None) => style,
+            (ThemeName::HighContrast, _) => style.fg(Color::Black).add_modifier(Modifier::BOLD),
+            (ThemeName::Colorblind, CardColor::Yellow | CardColor::Green) => style.fg(Color::Black),
+            (ThemeName::Colorblind, _) => style.fg(Color::White),
+            _ => style,
+        }
+    }
+
+    fn symbol(&self, color: CardColor) -> &'static str {
+        match color {
+            CardColor::Red => "R",
+            CardColor::Green => "G",
+            CardColor::Blue => "B",
+            CardColor::Yellow => "Y",
+            CardColor::None => "",
+        }
+    }
+
+    /// How wide a card is in a hand.
+    pub fn card_width(&self) -> usize {
+        if self.symbols() {
+            6
+        } else {
+            4
+        }
+    }
+
+    /// What a card shows: its value, after its color letter if the theme
+    /// has them.
+    pub fn text(&self, card: &Card) -> String {
+        if self.symbols() && card.color != CardColor::None {
+            format!("{} {}", self.symbol(card.color), card.value)
+        } else {
+            card.value.to_string()
+        }
+    }
+
+    /// `text` padded to `card_width`, for cards in a hand.
+    pub fn label(&self, card: &Card) -> String {
+        self.text(card).pad_to_width(self.card_width())
+    }
+}
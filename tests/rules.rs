@@ -0,0 +1,439 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Plays random games through the rules engine and checks after every move
+//! that no card went missing, the turn order and penalty follow the rules,
+//! and there's never more than one winner.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::sample::Index;
+use uno::cards::{Card, CardColor, CardValue, Deck};
+use uno::game::{Action, Direction, Game, RuleError};
+
+/// How many cards a full deck has.
+const DECK_SIZE: u16 = 108;
+/// How many moves a game gets to finish in.
+const MAX_MOVES: usize = 5000;
+
+fn name(seat: usize) -> String {
+    format!("player{}", seat)
+}
+
+/// A game dealt from a shuffled deck with `players` seated.
+fn new_game(seed: u64, players: usize) -> Game {
+    let mut game = Game::from_deck(Deck::with_seed(seed)).with_seed(seed);
+    for seat in 0..players {
+        game.apply(&Action::Join { name: name(seat) }).unwrap();
+    }
+
+    game
+}
+
+/// A game that starts on a red 5 with `players` seated, where the first
+/// player holds `hand` and everyone else holds blue cards.
+fn rigged(players: usize, hand: &[Card]) -> Game {
+    let mut cards = vec![Card::new(CardValue::Num(5), CardColor::Red)];
+    for _ in 0..players {
+        cards.extend((1..8).map(|n| Card::new(CardValue::Num(n), CardColor::Blue)));
+    }
+    cards.extend((1..10).map(|n| Card::new(CardValue::Num(n), CardColor::Yellow)));
+    cards.extend(hand.iter().copied());
+    for (id, card) in cards.iter_mut().enumerate() {
+        card.id = id as u16;
+    }
+
+    let hand = cards.split_off(cards.len() - hand.len());
+    let mut game = Game::from_deck(Deck::from_cards(cards));
+    for seat in 0..players {
+        game.apply(&Action::Join { name: name(seat) }).unwrap();
+    }
+    game.players[0].cards = hand;
+
+    game
+}
+
+/// Plays the first card in the first player's hand.
+fn play_first(game: &mut Game) {
+    let action = Action::UseCard {
+        name: name(0),
+        card_id: game.players[0].cards[0].id,
+        color: None,
+    };
+    game.apply(&action).unwrap();
+}
+
+/// Whose turn comes after `turn` when play goes in `direction`.
+fn after(turn: u8, direction: Direction, players: usize) -> u8 {
+    let players = players as u8;
+    match direction {
+        Direction::Left => (turn + 1) % players,
+        Direction::Right => (turn + players - 1) % players,
+    }
+}
+
+/// The ids of every card in the game, wherever it is, in order.
+fn card_ids(game: &Game) -> Vec<u16> {
+    let mut result: Vec<u16> = game
+        .deck()
+        .cards()
+        .iter()
+        .chain(game.discard())
+        .chain(game.players.iter().flat_map(|player| player.cards.iter()))
+        .map(|card| card.id)
+        .collect();
+    result.push(game.last_card.id);
+    result.sort_unstable();
+
+    result
+}
+
+/// What has to hold between any two moves.
+fn check_invariants(game: &Game) {
+    assert_eq!(card_ids(game), (0..DECK_SIZE).collect::<Vec<_>>());
+    assert!((game.current_turn as usize) < game.players.len());
+    assert!(game.players.iter().filter(|player| player.won()).count() <= 1);
+}
+
+/// Applies a legal `action` and checks that it moved the turn and changed
+/// the penalty the way the rules say.
+fn check_move(game: &mut Game, action: &Action) {
+    let before = game.clone();
+    let turn = before.current_turn;
+    let players = before.players.len();
+
+    game.apply(action).unwrap();
+    check_invariants(game);
+
+    match action {
+        Action::Draw { .. } => {
+            let wanted = before.plus.max(1) as usize;
+            let drawn =
+                game.players[turn as usize].card_num() - before.players[turn as usize].card_num();
+            // Drawing can only come up short once every card is in a hand.
+            assert!(
+                drawn == wanted
+                    || (drawn < wanted && game.deck_size() == 0 && game.discard().is_empty())
+            );
+            assert_eq!(game.plus, 0);
+            assert_eq!(game.direction(), before.direction());
+            assert_eq!(game.current_turn, after(turn, before.direction(), players));
+        }
+        Action::UseCard { card_id, .. } => {
+            let card = game.last_card;
+            assert_eq!(card.id, *card_id);
+            assert_ne!(card.color, CardColor::None);
+
+            let plus = match card.value {
+                CardValue::PlusTwo => before.plus + 2,
+                CardValue::WildPlusFour => before.plus + 4,
+                _ => {
+                    assert_eq!(before.plus, 0);
+                    0
+                }
+            };
+            assert_eq!(game.plus, plus);
+
+            let mut direction = before.direction();
+            if card.value == CardValue::Reverse {
+                direction.flip();
+            }
+            assert_eq!(game.direction(), direction);
+
+            if game.winner().is_some() {
+                assert_eq!(game.winner(), Some(turn));
+                return;
+            }
+
+            let next = match card.value {
+                // With two players a reverse comes straight back, like a skip.
+                CardValue::Reverse if players == 2 => turn,
+                CardValue::Skip => after(after(turn, direction, players), direction, players),
+                _ => after(turn, direction, players),
+            };
+            assert_eq!(game.current_turn, next);
+        }
+        _ => unreachable!("{:?} isn't a move", action),
+    }
+}
+
+/// Checks that nobody can move once the game is over.
+fn check_game_over(game: &mut Game) {
+    let before = serde_json::to_string(game).unwrap();
+
+    for seat in 0..game.players.len() {
+        assert!(game.legal_moves(seat as u8).is_empty());
+        let draw = Action::Draw { name: name(seat) };
+        assert_eq!(game.apply(&draw), Err(RuleError::GameOver));
+        for card_id in 0..DECK_SIZE {
+            let action = Action::UseCard {
+                name: name(seat),
+                card_id,
+                color: Some(CardColor::Red),
+            };
+            assert!(game.apply(&action).is_err());
+        }
+    }
+
+    assert_eq!(serde_json::to_string(game).unwrap(), before);
+}
+
+proptest! {
+    #[test]
+    fn random_moves_keep_the_rules(
+        seed in any::<u64>(),
+        players in 2usize..=6,
+        picks in vec(any::<Index>(), 1..400),
+    ) {
+        let mut game = new_game(seed, players);
+        check_invariants(&game);
+
+        for pick in picks {
+            if game.winner().is_some() {
+                check_game_over(&mut game);
+                break;
+            }
+
+            let moves = game.legal_moves(game.current_turn);
+            let draw = Action::Draw { name: name(game.current_turn as usize) };
+            prop_assert_eq!(moves.last(), Some(&draw));
+            check_move(&mut game, pick.get(&moves));
+        }
+    }
+
+    #[test]
+    fn games_end_with_one_winner(
+        seed in any::<u64>(),
+        players in 2usize..=6,
+        picks in vec(any::<Index>(), 64),
+    ) {
+        let mut game = new_game(seed, players);
+
+        // Play a card whenever there's one to play, so the game ends.
+        for turn in 0..MAX_MOVES {
+            if game.winner().is_some() {
+                break;
+            }
+
+            let mut moves = game.legal_moves(game.current_turn);
+            if moves.len() > 1 {
+                moves.pop();
+            }
+            check_move(&mut game, picks[turn % picks.len()].get(&moves));
+        }
+
+        prop_assert!(game.winner().is_some());
+        prop_assert_eq!(game.players.iter().filter(|player| player.won()).count(), 1);
+        check_game_over(&mut game);
+    }
+
+    #[test]
+    fn shuffled_decks_have_every_card(seed in any::<u64>()) {
+        let mut ids: Vec<u16> = Deck::with_seed(seed).cards().iter().map(|card| card.id).collect();
+        ids.sort_unstable();
+        prop_assert_eq!(ids, (0..DECK_SIZE).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn the_deck_has_108_cards() {
+    let deck = Deck::unshuffled();
+    let count = |value: CardValue, color: CardColor| {
+        deck.cards()
+            .iter()
+            .filter(|card| card.value == value && card.color == color)
+            .count()
+    };
+
+    assert_eq!(deck.len(), DECK_SIZE as usize);
+    for color in CardColor::ALL {
+        assert_eq!(count(CardValue::Num(0), color), 1);
+        for n in 1..10 {
+            assert_eq!(count(CardValue::Num(n), color), 2);
+        }
+        assert_eq!(count(CardValue::Skip, color), 2);
+        assert_eq!(count(CardValue::Reverse, color), 2);
+        assert_eq!(count(CardValue::PlusTwo, color), 2);
+    }
+    assert_eq!(count(CardValue::Wild, CardColor::None), 4);
+    assert_eq!(count(CardValue::WildPlusFour, CardColor::None), 4);
+}
+
+#[test]
+fn drawing_with_every_card_dealt_out() {
+    // 15 hands of 7 and the top card leave 2 cards to draw.
+    let mut game = new_game(7, 15);
+
+    for _ in 0..3 * game.players.len() {
+        let draw = Action::Draw {
+            name: name(game.current_turn as usize),
+        };
+        check_move(&mut game, &draw);
+    }
+    assert_eq!(game.deck_size(), 0);
+    assert!(game.discard().is_empty());
+
+    // Nor can a deck that's run dry deal a card.
+    let mut deck = Deck::from_cards(vec![]);
+    deck.give_card(&mut game.players[0]);
+    check_invariants(&game);
+}
+
+#[test]
+fn reverse_with_two_players_acts_as_skip() {
+    let mut game = rigged(
+        2,
+        &[Card::reverse(CardColor::Red), Card::reverse(CardColor::Red)],
+    );
+
+    play_first(&mut game);
+    assert_eq!(game.direction(), Direction::Left);
+    assert_eq!(game.current_turn, 0);
+}
+
+#[test]
+fn reverse_with_more_players_turns_play_around() {
+    let mut game = rigged(
+        4,
+        &[Card::reverse(CardColor::Red), Card::reverse(CardColor::Red)],
+    );
+
+    play_first(&mut game);
+    assert_eq!(game.direction(), Direction::Left);
+    assert_eq!(game.current_turn, 1);
+}
+
+#[test]
+fn skip_with_two_players_comes_back() {
+    let mut game = rigged(2, &[Card::skip(CardColor::Red), Card::skip(CardColor::Red)]);
+
+    play_first(&mut game);
+    assert_eq!(game.direction(), Direction::Right);
+    assert_eq!(game.current_turn, 0);
+}
+
+#[test]
+fn skip_with_more_players_skips_one() {
+    let mut game = rigged(4, &[Card::skip(CardColor::Red), Card::skip(CardColor::Red)]);
+
+    play_first(&mut game);
+    assert_eq!(game.current_turn, 2);
+}
+
+#[test]
+fn drawing_clears_the_penalty() {
+    let mut game = rigged(
+        3,
+        &[
+            Card::plus_two(CardColor::Red),
+            Card::plus_two(CardColor::Red),
+        ],
+    );
+
+    play_first(&mut game);
+    assert_eq!(game.plus, 2);
+    assert_eq!(game.current_turn, 2);
+
+    let play_blue = Action::UseCard {
+        name: name(2),
+        card_id: game.players[2].cards[0].id,
+        color: None,
+    };
+    assert_eq!(game.apply(&play_blue), Err(RuleError::MustDrawPenalty));
+
+    game.apply(&Action::Draw { name: name(2) }).unwrap();
+    assert_eq!(game.plus, 0);
+    assert_eq!(game.players[2].card_num(), 9);
+    assert_eq!(game.current_turn, 1);
+}
+
+#[test]
+fn drawing_out_of_turn_is_rejected() {
+    let mut game = rigged(
+        3,
+        &[
+            Card::plus_two(CardColor::Red),
+            Card::plus_two(CardColor::Red),
+        ],
+    );
+    play_first(&mut game);
+    let before = serde_json::to_string(&game).unwrap();
+
+    for seat in [0, 1] {
+        let draw = Action::Draw { name: name(seat) };
+        assert_eq!(game.apply(&draw), Err(RuleError::NotYourTurn));
+    }
+    assert_eq!(serde_json::to_string(&game).unwrap(), before);
+}
+
+#[test]
+fn drawing_takes_at_least_one_card() {
+    let mut game = rigged(
+        2,
+        &[Card::skip(CardColor::Blue), Card::skip(CardColor::Blue)],
+    );
+
+    game.apply(&Action::Draw { name: name(0) }).unwrap();
+    assert_eq!(game.players[0].card_num(), 3);
+    assert_eq!(game.current_turn, 1);
+    // There's no way left to pass or clear a penalty without drawing.
+    for action in [
+        r#"{"type":"take_cards","name":"player1","num":0}"#,
+        r#"{"type":"reset_plus"}"#,
+    ] {
+        assert!(serde_json::from_str::<Action>(action).is_err());
+    }
+}
+
+#[test]
+fn a_plus_four_on_a_penalty_needs_a_color() {
+    let mut game = rigged(
+        3,
+        &[
+            Card::plus_two(CardColor::Red),
+            Card::plus_two(CardColor::Red),
+        ],
+    );
+    let mut plus_four = Card::wild_plus_four();
+    plus_four.id = 1000;
+    game.players[2].cards.push(plus_four);
+
+    play_first(&mut game);
+    let play = |color| Action::UseCard {
+        name: name(2),
+        card_id: plus_four.id,
+        color,
+    };
+    assert_eq!(game.apply(&play(None)), Err(RuleError::NoColorChosen));
+    game.apply(&play(Some(CardColor::Red))).unwrap();
+    assert_eq!(game.plus, 6);
+}
+
+#[test]
+fn playing_the_last_card_wins() {
+    let mut game = rigged(3, &[Card::new(CardValue::Num(3), CardColor::Red)]);
+
+    play_first(&mut game);
+    assert_eq!(game.winner(), Some(0));
+    check_game_over(&mut game);
+}
@@ -0,0 +1,81 @@
+/*
+MIT License
+
+Copyright (c) 2021 P3qch
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+//! Checks that a seed always deals the same game.
+
+use uno::cards::Deck;
+use uno::game::{Action, Game};
+
+/// A game dealt from `seed` with two players seated.
+fn seeded_game(seed: u64) -> Game {
+    let mut game = Game::from_deck(Deck::with_seed(seed)).with_seed(seed);
+    for name in ["alice", "bob"] {
+        game.apply(&Action::Join {
+            name: name.to_string(),
+        })
+        .unwrap();
+    }
+
+    game
+}
+
+fn ids(deck: &Deck) -> Vec<u16> {
+    deck.cards().iter().map(|card| card.id).collect()
+}
+
+#[test]
+fn seeded_decks_deal_the_same_cards() {
+    assert_eq!(
+        ids(&Deck::with_seed(42))[..10],
+        [22, 107, 84, 103, 8, 65, 29, 41, 85, 81]
+    );
+    assert_eq!(ids(&Deck::with_seed(42)), ids(&Deck::with_seed(42)));
+    assert_ne!(ids(&Deck::with_seed(42)), ids(&Deck::with_seed(43)));
+}
+
+#[test]
+fn seeded_games_match() {
+    let mut first = seeded_game(7);
+    let mut second = seeded_game(7);
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+
+    // Keep playing, so the draws and any reshuffles have to match too.
+    for _ in 0..200 {
+        if first.winner().is_some() {
+            break;
+        }
+        let action = first.legal_moves(first.current_turn).remove(0);
+        assert_eq!(first.apply(&action), second.apply(&action));
+    }
+    assert_eq!(
+        serde_json::to_string(&first).unwrap(),
+        serde_json::to_string(&second).unwrap()
+    );
+    assert_ne!(
+        serde_json::to_string(&seeded_game(7)).unwrap(),
+        serde_json::to_string(&seeded_game(8)).unwrap()
+    );
+}